@@ -0,0 +1,161 @@
+//! Bone hierarchies generated from strand topology.
+//!
+//! A [`BoneHierarchy`] places a joint every `interval` points along each strand,
+//! oriented by the strand point's rotation. Joints chain along their strand, and the
+//! first joint of a branch hangs off the parent-strand joint that governs its
+//! attachment point, so posing a joint carries every branch above it.
+
+use crate::skeleton::Skeleton;
+use glam::{Mat4, Quat, Vec3};
+use std::ops::Range;
+
+/// A joint placed at a strand point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Joint {
+    /// Index of the parent joint, or `None` for a root.
+    pub parent: Option<usize>,
+    /// Strand the joint lies on.
+    pub strand: usize,
+    /// Index of the strand point the joint sits at.
+    pub point: usize,
+    /// World-space position (the point's position).
+    pub position: Vec3,
+    /// World-space orientation (the point's rotation).
+    pub rotation: Quat,
+}
+
+impl Joint {
+    /// World transform of the joint in its rest pose.
+    pub fn world_matrix(&self) -> Mat4 {
+        Mat4::from_rotation_translation(self.rotation, self.position)
+    }
+}
+
+/// Joints generated from the strands of a [`Skeleton`].
+#[derive(Clone, Debug, Default)]
+pub struct BoneHierarchy {
+    /// Joints ordered so that every parent precedes its children.
+    pub joints: Vec<Joint>,
+    interval: usize,
+    /// Joints of each strand, which are contiguous in `joints`.
+    strand_joints: Vec<Range<usize>>,
+}
+
+impl BoneHierarchy {
+    /// Places a joint at every `interval`-th point of each strand, starting with the
+    /// first (`interval` is clamped to at least 1).
+    ///
+    /// Strands with fewer than 2 points carry no geometry and get no joints; branches
+    /// attached to them hang off the nearest ancestor with joints instead.
+    /// [Detached](crate::StrandInfo::detached) strands start a new root joint.
+    pub fn from_skeleton(skeleton: &Skeleton, interval: usize) -> Self {
+        let interval = interval.max(1);
+        let mut hierarchy = Self {
+            joints: Vec::new(),
+            interval,
+            strand_joints: Vec::with_capacity(skeleton.strands.len()),
+        };
+
+        for (strand, points) in skeleton.strands.iter().enumerate() {
+            let start = hierarchy.joints.len();
+            if points.len() < 2 {
+                hierarchy.strand_joints.push(start..start);
+                continue;
+            }
+            let mut parent = hierarchy.attachment_joint(skeleton, strand);
+            for (point, p) in points.iter().enumerate().step_by(interval) {
+                hierarchy.joints.push(Joint {
+                    parent,
+                    strand,
+                    point,
+                    position: p.position,
+                    rotation: p.rotation,
+                });
+                parent = Some(hierarchy.joints.len() - 1);
+            }
+            hierarchy.strand_joints.push(start..hierarchy.joints.len());
+        }
+        hierarchy
+    }
+
+    /// The joint that `strand`'s first joint hangs off: the one governing its attachment
+    /// point, walking up past ancestors without joints.
+    fn attachment_joint(&self, skeleton: &Skeleton, strand: usize) -> Option<usize> {
+        let mut child = strand;
+        // Parents always precede their children, so the parent's joints already exist
+        while let Some(parent) = skeleton.parent(child).filter(|&p| p < child) {
+            let point = skeleton.strand_info(child).parent_point;
+            if let Some(joint) = self.joint_at(parent, point) {
+                return Some(joint);
+            }
+            child = parent;
+        }
+        None
+    }
+
+    /// Number of points between consecutive joints on a strand.
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// The joint governing `point` of `strand`: the last joint at or before it, or `None`
+    /// if the strand has no joints.
+    pub fn joint_at(&self, strand: usize, point: usize) -> Option<usize> {
+        let range = self.strand_joints.get(strand)?;
+        let joint = range.start + point / self.interval;
+        range.contains(&joint).then_some(joint)
+    }
+
+    /// The joints nearest to `point` of `strand` along the strand, with weights summing
+    /// to 1.
+    ///
+    /// A point between two joints is split between them by arc length; a point past
+    /// the strand's last joint follows that joint alone (the second weight is 0).
+    /// Returns `None` for strands without joints.
+    pub fn influences(
+        &self,
+        skeleton: &Skeleton,
+        strand: usize,
+        point: usize,
+    ) -> Option<[(usize, f32); 2]> {
+        let joint = self.joint_at(strand, point)?;
+        let next = joint + 1;
+        let points = &skeleton.strands[strand];
+        if !self.strand_joints[strand].contains(&next) {
+            return Some([(joint, 1.0), (joint, 0.0)]);
+        }
+
+        let arc = |from: usize, to: usize| -> f32 {
+            points[from..=to]
+                .windows(2)
+                .map(|w| w[0].position.distance(w[1].position))
+                .sum()
+        };
+        let start = self.joints[joint].point;
+        let span = arc(start, self.joints[next].point);
+        let t = if span > 0.0 {
+            arc(start, point) / span
+        } else {
+            0.0
+        };
+        Some([(joint, 1.0 - t), (next, t)])
+    }
+
+    /// Rest transform of `joint` relative to its parent, as `(translation, rotation)`.
+    pub fn local_transform(&self, joint: usize) -> (Vec3, Quat) {
+        let Joint {
+            position, rotation, ..
+        } = self.joints[joint];
+        match self.joints[joint].parent {
+            Some(parent) => {
+                let parent = &self.joints[parent];
+                let inverse = parent.rotation.inverse();
+                (
+                    inverse * (position - parent.position),
+                    (inverse * rotation).normalize(),
+                )
+            }
+            None => (position, rotation),
+        }
+    }
+}
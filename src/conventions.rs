@@ -0,0 +1,132 @@
+//! Coordinate-system and handedness conventions.
+//!
+//! The interpreter always works in a canonical frame: the turtle's heading is its
+//! local +Y axis and world up is +Y. [`Conventions`] describe the frame the output
+//! should be expressed in. World-space inputs (tropism vectors, environments) are
+//! mapped into the canonical frame and every emitted point, prop, polygon and query
+//! value is mapped back out, so `$` keeps aligning to the configured world up.
+
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::FRAC_PI_2;
+
+/// A positive coordinate axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Axis {
+    X,
+    #[default]
+    Y,
+    Z,
+}
+
+impl Axis {
+    /// Unit vector along the axis.
+    pub fn unit(self) -> Vec3 {
+        match self {
+            Axis::X => Vec3::X,
+            Axis::Y => Vec3::Y,
+            Axis::Z => Vec3::Z,
+        }
+    }
+
+    /// Proper rotation taking +Y onto this axis.
+    pub(crate) fn rotation_from_y(self) -> Quat {
+        match self {
+            Axis::X => Quat::from_rotation_z(-FRAC_PI_2),
+            Axis::Y => Quat::IDENTITY,
+            Axis::Z => Quat::from_rotation_x(FRAC_PI_2),
+        }
+    }
+}
+
+/// Sense of positive rotation angles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Handedness {
+    /// Positive angles turn counter-clockwise when looking down the rotation axis
+    /// (right-hand rule).
+    #[default]
+    Right,
+    /// Positive angles turn clockwise when looking down the rotation axis.
+    Left,
+}
+
+/// Output frame conventions for [`TurtleConfig::conventions`](crate::TurtleConfig::conventions).
+///
+/// The default is Y-up with a +Y heading and right-handed rotations (Bevy, glTF).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Conventions {
+    /// Local axis of every emitted rotation that points along the heading.
+    pub heading: Axis,
+    /// World axis the turtle initially grows along and that `$` aligns to.
+    pub world_up: Axis,
+    /// Sense of Yaw, Pitch and Roll angles.
+    pub handedness: Handedness,
+}
+
+impl Conventions {
+    /// Y-up world with a +Y heading (Bevy, glTF). Same as the default.
+    pub const Y_UP: Self = Self {
+        heading: Axis::Y,
+        world_up: Axis::Y,
+        handedness: Handedness::Right,
+    };
+
+    /// Z-up world with a +Z heading (Blender, Unreal, USD).
+    pub const Z_UP: Self = Self {
+        heading: Axis::Z,
+        world_up: Axis::Z,
+        handedness: Handedness::Right,
+    };
+
+    /// Precomputes the transforms between the canonical and configured frames.
+    pub(crate) fn frame(&self) -> Frame {
+        Frame {
+            world: self.world_up.rotation_from_y(),
+            local: self.heading.rotation_from_y(),
+            angle_sign: match self.handedness {
+                Handedness::Right => 1.0,
+                Handedness::Left => -1.0,
+            },
+        }
+    }
+}
+
+/// Canonical-to-output transforms derived from [`Conventions`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Frame {
+    /// Canonical world to output world.
+    world: Quat,
+    /// Canonical local axes to output local axes.
+    local: Quat,
+    /// Multiplier applied to every rotation angle.
+    pub(crate) angle_sign: f32,
+}
+
+impl Frame {
+    /// Maps a canonical world-space point or direction to the output frame.
+    pub(crate) fn output_vector(&self, v: Vec3) -> Vec3 {
+        self.world * v
+    }
+
+    /// Maps an output world-space point or direction to the canonical frame.
+    pub(crate) fn canonical_vector(&self, v: Vec3) -> Vec3 {
+        self.world.inverse() * v
+    }
+
+    /// Maps a canonical orientation to the output frame and local axes.
+    pub(crate) fn output_rotation(&self, rotation: Quat) -> Quat {
+        (self.world * rotation * self.local.inverse()).normalize()
+    }
+
+    /// Re-expresses an output orientation with canonical local axes (heading +Y),
+    /// keeping the output world frame.
+    pub(crate) fn canonical_local(&self, rotation: Quat) -> Quat {
+        (rotation * self.local).normalize()
+    }
+
+    /// Inverse of [`Self::canonical_local`].
+    pub(crate) fn output_local(&self, rotation: Quat) -> Quat {
+        (rotation * self.local.inverse()).normalize()
+    }
+}
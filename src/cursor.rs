@@ -0,0 +1,959 @@
+//! Resumable, step-wise interpretation.
+//!
+//! An [`InterpretationCursor`] owns the turtle, branch stack and output sink (a
+//! partial [`Skeleton`] by default) of an interpretation in progress. It advances by a number of symbols or a time
+//! budget and returns control in between, so very long strings can be interpreted
+//! across several frames while partial results are displayed.
+
+use crate::conventions::{Axis, Frame};
+use crate::diagnostics::{
+    Diagnostic, DiagnosticKind, InterpretError, InterpretationReport, UnmappedSymbol,
+};
+use crate::environment::{Environment, ObstaclePolicy, SURFACE_EPSILON, TerminatedBranch, march};
+use crate::growth::GrowthCurve;
+use crate::interpreter::TurtleInterpreter;
+use crate::jitter::{BranchPath, JitterChannel, JitterConfig};
+use crate::limits::Limit;
+use crate::query::{QueryKind, QueryPatch};
+use crate::sink::TurtleSink;
+use crate::skeleton::{
+    DEDUP_DISTANCE_SQUARED, Skeleton, SkeletonPoint, SkeletonPolygon, SkeletonProp, StrandInfo,
+};
+use crate::turtle::{TurtleOp, TurtleState};
+use glam::{Mat3, Quat, Vec3, Vec4};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::time::{Duration, Instant};
+use symbios::SymbiosState;
+
+/// Symbols interpreted between clock checks in [`InterpretationCursor::advance_for`].
+const CLOCK_CHECK_INTERVAL: usize = 256;
+
+/// Symbols interpreted between polls of the cancellation token and progress callback.
+const POLL_INTERVAL: usize = 256;
+
+/// Maximum number of surface slides per Draw under [`ObstaclePolicy::Deflect`].
+const MAX_DEFLECTIONS: usize = 8;
+
+/// Turtle and bookkeeping saved by Push and restored by Pop.
+struct StackFrame {
+    turtle: TurtleState,
+    path: BranchPath,
+    /// `(strand, point)` the branch grew from; the resumed parent attaches here too.
+    attachment: Option<(usize, usize)>,
+    /// Whether the parent branch had been stopped by an obstacle.
+    blocked: bool,
+    /// Width growth factor of the parent's last Draw.
+    width_growth: f32,
+    /// Index of the `[` module, for reporting unclosed branches.
+    push_index: usize,
+}
+
+/// An interpretation in progress, created by [`TurtleInterpreter::cursor`],
+/// [`TurtleInterpreter::cursor_at`] or [`TurtleInterpreter::cursor_into`].
+///
+/// Borrows the interpreter and the state for its whole lifetime. Interpreting a
+/// string in chunks yields exactly the same output as
+/// [`TurtleInterpreter::build_skeleton`].
+pub struct InterpretationCursor<'a, S: TurtleSink = Skeleton> {
+    interpreter: &'a TurtleInterpreter,
+    state: &'a SymbiosState,
+    time: Option<f64>,
+    index: usize,
+    sink: S,
+    // Index of the last point emitted, as `(strand, point)`, and its position for deduplication
+    tip: Option<(usize, usize)>,
+    tip_position: Vec3,
+    strand_count: usize,
+    strand_open: bool,
+    // Canonical-to-output transforms from `TurtleConfig::conventions`
+    frame: Frame,
+    patches: Vec<QueryPatch>,
+    turtle: TurtleState,
+    stack: Vec<StackFrame>,
+    path: BranchPath,
+    // Polygons use their own stack so vertices recorded inside `[ ]` branches
+    // still contribute to the enclosing polygon, as in ABOP leaf definitions.
+    polygon_stack: Vec<SkeletonPolygon>,
+    // Set once the current branch runs into an obstacle; cleared by the closing `]`.
+    blocked: bool,
+    // Width growth of the last Draw on the current branch, applied to every point it starts
+    width_growth: f32,
+    report: InterpretationReport,
+    // Position of each unmapped symbol in `report.unmapped_symbols`
+    unmapped: HashMap<u16, usize>,
+    // Index of a module the state failed to produce; interpretation stops there
+    unreadable: Option<usize>,
+    // Counters for `TurtleConfig::limits` and polling
+    steps: usize,
+    point_count: usize,
+    prop_count: usize,
+    drawn_length: f32,
+    // Set once a limit or cancellation stopped interpretation; all further output is dropped
+    stopped: bool,
+}
+
+impl<'a, S: TurtleSink> InterpretationCursor<'a, S> {
+    /// Starts an interpretation of `state` into `sink`; `time` enables age-driven
+    /// growth scaling.
+    pub(crate) fn new(
+        interpreter: &'a TurtleInterpreter,
+        state: &'a SymbiosState,
+        time: Option<f64>,
+        sink: S,
+    ) -> Self {
+        let config = &interpreter.config;
+        let frame = config.conventions.frame();
+        Self {
+            interpreter,
+            state,
+            time,
+            index: 0,
+            sink,
+            tip: None,
+            tip_position: Vec3::ZERO,
+            strand_count: 0,
+            strand_open: false,
+            frame,
+            patches: Vec::new(),
+            turtle: TurtleState {
+                width: config.initial_width,
+                tropism: config.tropism.map(|t| frame.canonical_vector(t)),
+                elasticity: config.elasticity,
+                ..Default::default()
+            },
+            stack: Vec::new(),
+            path: BranchPath::root(config.jitter.map_or(0, |j| j.seed)),
+            polygon_stack: Vec::new(),
+            blocked: false,
+            width_growth: 1.0,
+            report: InterpretationReport::default(),
+            unmapped: HashMap::new(),
+            unreadable: None,
+            steps: 0,
+            point_count: 0,
+            prop_count: 0,
+            drawn_length: 0.0,
+            stopped: false,
+        }
+    }
+
+    /// Interprets up to `count` further symbols. Returns `true` once the whole
+    /// string has been interpreted.
+    pub fn advance(&mut self, count: usize) -> bool {
+        for _ in 0..count {
+            if self.is_finished() {
+                break;
+            }
+            if self.steps.is_multiple_of(POLL_INTERVAL) {
+                self.poll();
+                if self.is_finished() {
+                    break;
+                }
+            }
+            self.steps += 1;
+            self.step();
+        }
+        self.is_finished()
+    }
+
+    /// Interprets symbols until `budget` has elapsed or the string is exhausted.
+    /// Returns `true` once the whole string has been interpreted.
+    ///
+    /// The clock is checked every few hundred symbols, so a chunk may overrun the
+    /// budget slightly.
+    pub fn advance_for(&mut self, budget: Duration) -> bool {
+        let start = Instant::now();
+        while !self.advance(CLOCK_CHECK_INTERVAL) {
+            if start.elapsed() >= budget {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns `true` once every symbol has been interpreted, or interpretation was
+    /// stopped by a limit or cancellation.
+    pub fn is_finished(&self) -> bool {
+        self.index >= self.state.len()
+    }
+
+    /// Index of the next symbol to interpret.
+    pub fn position(&self) -> usize {
+        self.index
+    }
+
+    /// Fraction of the string interpreted so far, in `[0, 1]`.
+    pub fn progress(&self) -> f32 {
+        match self.state.len() {
+            0 => 1.0,
+            len => self.index.min(len) as f32 / len as f32,
+        }
+    }
+
+    /// The sink receiving output.
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// Problems found so far. Unclosed branches are only reported once the
+    /// interpretation finishes.
+    pub fn report(&self) -> &InterpretationReport {
+        &self.report
+    }
+
+    /// Interprets any remaining symbols, ends the last strand and returns the sink
+    /// together with the patches captured by [`TurtleOp::Query`] modules.
+    ///
+    /// Skeleton post-processes such as
+    /// [`TurtleConfig::rotation_minimizing_frames`](crate::TurtleConfig::rotation_minimizing_frames)
+    /// are not applied; use [`InterpretationCursor::finish`] for that.
+    pub fn finish_into(self) -> (S, Vec<QueryPatch>) {
+        let (sink, patches, _) = self.finish_parts();
+        (sink, patches)
+    }
+
+    /// Interprets any remaining symbols, ends the last strand and completes the report.
+    fn finish_parts(mut self) -> (S, Vec<QueryPatch>, InterpretationReport) {
+        self.advance(usize::MAX);
+        self.end_strand();
+        if !self.stopped {
+            for frame in &self.stack {
+                self.report.diagnostics.push(Diagnostic {
+                    module_index: frame.push_index,
+                    kind: DiagnosticKind::UnclosedPush,
+                });
+            }
+        }
+        if let Some(progress) = &self.interpreter.progress {
+            progress(1.0);
+        }
+        (self.sink, self.patches, self.report)
+    }
+
+    /// Checks the cancellation token and reports progress.
+    fn poll(&mut self) {
+        if let Some(token) = &self.interpreter.cancellation
+            && token.is_cancelled()
+        {
+            self.stop(self.index, DiagnosticKind::Cancelled);
+            return;
+        }
+        if let Some(progress) = &self.interpreter.progress {
+            progress(self.progress());
+        }
+    }
+
+    /// Stops interpretation, reporting `kind` at module `module_index`.
+    fn stop(&mut self, module_index: usize, kind: DiagnosticKind) {
+        self.diagnose(module_index, kind);
+        self.stopped = true;
+        self.end_strand();
+        self.index = self.state.len();
+    }
+
+    /// Returns `true` if emitting one more item would exceed `limit`, stopping
+    /// interpretation at the current module if so.
+    fn exceeds(&mut self, limit: Limit) -> bool {
+        if self.stopped {
+            return true;
+        }
+        let limits = &self.interpreter.config.limits;
+        let exceeded = match limit {
+            Limit::Strands => limits
+                .max_strands
+                .is_some_and(|max| self.strand_count >= max),
+            Limit::Points => limits.max_points.is_some_and(|max| self.point_count >= max),
+            Limit::Props => limits.max_props.is_some_and(|max| self.prop_count >= max),
+            Limit::DrawnLength => false,
+        };
+        if exceeded {
+            // The module being interpreted is the one before the cursor
+            self.stop(
+                self.index.saturating_sub(1),
+                DiagnosticKind::LimitExceeded { limit },
+            );
+        }
+        exceeded
+    }
+
+    /// Records a problem at module `module_index`.
+    fn diagnose(&mut self, module_index: usize, kind: DiagnosticKind) {
+        self.report
+            .diagnostics
+            .push(Diagnostic { module_index, kind });
+    }
+
+    /// Counts an occurrence of a symbol with no op mapped.
+    fn record_unmapped(&mut self, symbol: u16, module_index: usize) {
+        match self.unmapped.get(&symbol) {
+            Some(&slot) => self.report.unmapped_symbols[slot].count += 1,
+            None => {
+                self.unmapped
+                    .insert(symbol, self.report.unmapped_symbols.len());
+                self.report.unmapped_symbols.push(UnmappedSymbol {
+                    symbol,
+                    first_index: module_index,
+                    count: 1,
+                });
+            }
+        }
+    }
+
+    /// Ends the open strand, if any.
+    fn end_strand(&mut self) {
+        if self.strand_open {
+            self.sink.end_strand();
+            self.strand_open = false;
+        }
+    }
+
+    /// Maps a canonical point to the output frame.
+    fn output_point(&self, mut point: SkeletonPoint) -> SkeletonPoint {
+        point.position = self.frame.output_vector(point.position);
+        point.rotation = self.frame.output_rotation(point.rotation);
+        point
+    }
+
+    /// Ends the open strand and starts a new one at canonical `point`.
+    fn begin_strand(&mut self, point: SkeletonPoint, info: StrandInfo) {
+        let point = self.output_point(point);
+        self.begin_output_strand(point, info);
+    }
+
+    /// Ends the open strand and starts a new one at `point`, already in the output frame.
+    fn begin_output_strand(&mut self, point: SkeletonPoint, info: StrandInfo) {
+        if self.exceeds(Limit::Strands) || self.exceeds(Limit::Points) {
+            return;
+        }
+        self.end_strand();
+        self.sink.begin_strand(point, info);
+        self.tip = Some((self.strand_count, 0));
+        self.tip_position = point.position;
+        self.strand_count += 1;
+        self.point_count += 1;
+        self.strand_open = true;
+    }
+
+    /// Appends canonical `point` to the current strand, dropping near-duplicates.
+    fn add_point(&mut self, point: SkeletonPoint) {
+        let point = self.output_point(point);
+        match self.tip {
+            Some((strand, index)) if self.strand_open => {
+                if self.tip_position.distance_squared(point.position) < DEDUP_DISTANCE_SQUARED
+                    || self.exceeds(Limit::Points)
+                {
+                    return;
+                }
+                self.sink.add_point(point);
+                self.point_count += 1;
+                self.tip = Some((strand, index + 1));
+                self.tip_position = point.position;
+            }
+            _ => self.begin_output_strand(point, StrandInfo::default()),
+        }
+    }
+
+    /// Emits a prop placed in the canonical frame.
+    fn spawn_prop(&mut self, mut prop: SkeletonProp) {
+        if self.exceeds(Limit::Props) {
+            return;
+        }
+        self.prop_count += 1;
+        prop.position = self.frame.output_vector(prop.position);
+        prop.rotation = self.frame.output_rotation(prop.rotation);
+        self.sink.spawn_prop(prop);
+    }
+
+    /// Emits a polygon traced in the canonical frame.
+    fn add_polygon(&mut self, mut polygon: SkeletonPolygon) {
+        if self.stopped {
+            return;
+        }
+        for vertex in &mut polygon.vertices {
+            *vertex = self.frame.output_vector(*vertex);
+        }
+        self.sink.add_polygon(polygon);
+    }
+
+    /// Emits a terminated branch whose position is in the canonical frame.
+    fn terminate_branch(&mut self, mut branch: TerminatedBranch) {
+        if self.stopped {
+            return;
+        }
+        branch.position = self.frame.output_vector(branch.position);
+        self.sink.terminate_branch(branch);
+    }
+
+    /// Moves the turtle `len` along its heading through `env`, resolving obstacle
+    /// contacts with `policy`.
+    ///
+    /// Under [`ObstaclePolicy::Deflect`] every slide adds a corner point to the current
+    /// strand; a head-on contact, or length left after [`MAX_DEFLECTIONS`] slides, stops
+    /// the branch. Returns `true` if the branch was stopped at a surface.
+    fn advance_in_environment(
+        &mut self,
+        env: &dyn Environment,
+        policy: ObstaclePolicy,
+        len: f32,
+    ) -> bool {
+        let mut remaining = len;
+        for _ in 0..=MAX_DEFLECTIONS {
+            let dir = self.turtle.up();
+            let Some(t) = march(env, self.turtle.position, dir, remaining) else {
+                self.turtle.position += dir * remaining;
+                return false;
+            };
+            self.turtle.position += dir * t;
+            remaining -= t;
+            if policy != ObstaclePolicy::Deflect {
+                return true;
+            }
+
+            let normal = env.normal(self.turtle.position);
+            let tangent = (dir - normal * dir.dot(normal)).normalize_or_zero();
+            if remaining <= SURFACE_EPSILON {
+                return false;
+            }
+            if tangent == Vec3::ZERO {
+                // Head-on contact: nothing to slide along, so the branch stops as under Stop
+                return true;
+            }
+            self.add_point(skeleton_point(&self.turtle, self.width_growth));
+            // Lift off the surface so the next march does not report contact immediately
+            self.turtle.position += normal * (2.0 * SURFACE_EPSILON);
+            self.turtle.align_up_to(tangent);
+        }
+        // Still short of the full length after every allowed slide
+        true
+    }
+
+    /// Returns the index of the `]` closing the branch that is open at `from`, or the
+    /// end of the string if it is never closed.
+    fn branch_end(&self, from: usize) -> usize {
+        let mut depth = 0usize;
+        for i in from..self.state.len() {
+            let Some(view) = self.state.get_view(i) else {
+                break;
+            };
+            match self
+                .interpreter
+                .op_map
+                .get(view.sym as usize)
+                .and_then(Option::as_ref)
+            {
+                Some(TurtleOp::Push) => depth += 1,
+                Some(TurtleOp::Pop) if depth == 0 => return i,
+                Some(TurtleOp::Pop) => depth -= 1,
+                _ => {}
+            }
+        }
+        self.state.len()
+    }
+
+    /// Interprets the symbol at the cursor and moves past it.
+    fn step(&mut self) {
+        let i = self.index;
+        let interpreter = self.interpreter;
+        let state = self.state;
+        let time = self.time;
+        let view = match state.get_view(i) {
+            Some(v) => v,
+            None => {
+                self.unreadable = Some(i);
+                self.index = state.len();
+                return;
+            }
+        };
+        self.index += 1;
+
+        let op = match interpreter
+            .op_map
+            .get(view.sym as usize)
+            .and_then(Option::as_ref)
+        {
+            Some(op) => op,
+            None => {
+                self.record_unmapped(view.sym, i);
+                &TurtleOp::Ignore
+            }
+        };
+        let finite = match view.params.iter().position(|&x| !(x as f32).is_finite()) {
+            Some(param) if *op != TurtleOp::Ignore => {
+                self.diagnose(i, DiagnosticKind::NonFiniteParameter { param });
+                false
+            }
+            _ => true,
+        };
+        let jitter = interpreter.config.jitter;
+        let key = self.path.advance();
+        // Helper to evaluate a growth curve at this module's age
+        let growth = |curve: &GrowthCurve| -> f32 {
+            time.map_or(1.0, |t| {
+                curve.evaluate((view.age + (t - state.current_time)) as f32)
+            })
+        };
+        // Helper to sample a jitter channel scaled by its configured amplitude
+        let jitter_by = |amplitude: fn(&JitterConfig) -> f32, channel: JitterChannel| -> f32 {
+            jitter.map_or(0.0, |j| amplitude(&j) * key.sample(channel))
+        };
+        // Helper to get param at index with default
+        let p = |idx: usize, def: f32| -> f32 {
+            view.params.get(idx).map(|&x| x as f32).unwrap_or(def)
+        };
+        let p0 = p(0, 0.0); // Common case helper
+        // Helper to read an angle param in the configured unit, returning radians
+        let get_angle = || -> f32 {
+            view.params
+                .first()
+                .map_or(interpreter.config.default_angle, |&x| {
+                    interpreter.config.angle_unit.to_radians(x as f32)
+                })
+        };
+        let get_val =
+            |default: f32| -> f32 { view.params.first().map(|&x| x as f32).unwrap_or(default) };
+
+        match op {
+            TurtleOp::Draw | TurtleOp::Move => {
+                if self.blocked {
+                    return;
+                }
+                let is_move = matches!(op, TurtleOp::Move);
+                let mut len = get_val(interpreter.config.default_step)
+                    * (1.0 + jitter_by(|j| j.step, JitterChannel::Step));
+                if !is_move {
+                    len *= growth(&interpreter.config.growth.length);
+                    self.width_growth = growth(&interpreter.config.growth.width);
+                    if let Some(max) = interpreter.config.limits.max_drawn_length
+                        && self.drawn_length + len > max
+                    {
+                        let limit = Limit::DrawnLength;
+                        self.stop(i, DiagnosticKind::LimitExceeded { limit });
+                        return;
+                    }
+                    self.drawn_length += len;
+                }
+
+                if self.tip.is_none() {
+                    self.begin_strand(
+                        skeleton_point(&self.turtle, self.width_growth),
+                        StrandInfo {
+                            depth: self.stack.len(),
+                            ..Default::default()
+                        },
+                    );
+                }
+                let move_origin = self.tip;
+                let mut hit = false;
+
+                if !is_move {
+                    match &interpreter.environment {
+                        Some(env) => {
+                            let env = FramedEnvironment {
+                                inner: env.as_ref(),
+                                frame: self.frame,
+                            };
+                            hit = self.advance_in_environment(
+                                &env,
+                                interpreter.config.obstacle_policy,
+                                len,
+                            );
+                        }
+                        None => self.turtle.position += self.turtle.up() * len,
+                    }
+
+                    if let Some(t_vec) = self.turtle.tropism
+                        && self.turtle.elasticity > 0.0
+                    {
+                        self.turtle.bend_toward(t_vec, self.turtle.elasticity);
+                    }
+                    for term in &interpreter.config.tropisms {
+                        let (t_vec, elasticity) =
+                            term.sample(self.frame.output_vector(self.turtle.position));
+                        if elasticity != 0.0 {
+                            self.turtle
+                                .bend_toward(self.frame.canonical_vector(t_vec), elasticity);
+                        }
+                    }
+                } else {
+                    self.turtle.position += self.turtle.up() * len;
+                }
+
+                // Push Node with FULL STATE
+                let point = skeleton_point(&self.turtle, self.width_growth);
+                if is_move {
+                    // Force new strand, recording the one it continues
+                    let info = StrandInfo {
+                        detached: true,
+                        ..strand_info(move_origin, self.stack.len())
+                    };
+                    self.begin_strand(point, info);
+                } else {
+                    self.add_point(point);
+                }
+
+                if hit {
+                    self.blocked = true;
+                    if interpreter.config.obstacle_policy == ObstaclePolicy::Terminate
+                        && let Some((strand, _)) = self.tip
+                    {
+                        self.terminate_branch(TerminatedBranch {
+                            module_index: i,
+                            strand,
+                            position: self.turtle.position,
+                        });
+                    }
+                }
+            }
+            TurtleOp::Yaw(sign) => {
+                let angle = get_angle() * sign + jitter_by(|j| j.angle, JitterChannel::Angle);
+                let angle = angle * self.frame.angle_sign;
+                self.turtle.rotate_local_z(angle);
+            }
+            TurtleOp::Pitch(sign) => {
+                let angle = get_angle() * sign + jitter_by(|j| j.angle, JitterChannel::Angle);
+                let angle = angle * self.frame.angle_sign;
+                self.turtle.rotate_local_x(angle);
+            }
+            TurtleOp::Roll(sign) => {
+                let angle = get_angle() * sign + jitter_by(|j| j.angle, JitterChannel::Angle);
+                let angle = angle * self.frame.angle_sign;
+                self.turtle.rotate_local_y(angle);
+            }
+            TurtleOp::TurnAround => {
+                self.turtle.rotate_local_z(PI);
+            }
+            TurtleOp::Vertical => {
+                let h = self.turtle.up();
+                let v = Vec3::Y;
+                let l = v.cross(h).normalize_or_zero();
+                if l.length_squared() > 0.001 {
+                    let u = h.cross(l).normalize();
+                    let rot_matrix = Mat3::from_cols(-l, h, u);
+                    self.turtle.rotation = Quat::from_mat3(&rot_matrix);
+                }
+            }
+            TurtleOp::SetWidth => {
+                self.turtle.width = get_val(self.turtle.width)
+                    * (1.0 + jitter_by(|j| j.width, JitterChannel::Width));
+                if self.turtle.width < 0.0 {
+                    let width = self.turtle.width;
+                    self.diagnose(i, DiagnosticKind::NegativeWidth { width });
+                }
+            }
+            TurtleOp::SetColor => {
+                // Logic: Supports 1 arg (Grayscale), 3 args (RGB), 4 args (RGBA)
+                let count = view.params.len();
+                match count {
+                    1 => self.turtle.color = Vec4::new(p0, p0, p0, 1.0),
+                    3 => self.turtle.color = Vec4::new(p(0, 0.), p(1, 0.), p(2, 0.), 1.0),
+                    4 => self.turtle.color = Vec4::new(p(0, 0.), p(1, 0.), p(2, 0.), p(3, 1.)),
+                    _ => {} // No change if no params
+                }
+            }
+            TurtleOp::SetMaterial => {
+                if let Some(&value) = view.params.first()
+                    && finite
+                    && !fits_integer(value, u8::MAX)
+                {
+                    self.diagnose(i, DiagnosticKind::MaterialOutOfRange { value });
+                }
+                self.turtle.material_id = p0 as u8;
+            }
+            TurtleOp::SetUVScale => {
+                self.turtle.uv_scale = get_val(1.0).max(0.0);
+            }
+            TurtleOp::SetTropism => match view.params.len() {
+                0 => self.turtle.tropism = None,
+                3 => {
+                    let target = Vec3::new(p(0, 0.), p(1, 0.), p(2, 0.));
+                    self.turtle.tropism = Some(self.frame.canonical_vector(target));
+                }
+                _ => {} // No change on malformed params
+            },
+            TurtleOp::SetElasticity => {
+                self.turtle.elasticity = get_val(self.turtle.elasticity);
+            }
+            TurtleOp::Push => {
+                if self.stack.len() >= interpreter.config.max_stack_depth {
+                    // Skip the whole subtree, including its `]`, so the parent is unaffected
+                    let end = self.branch_end(self.index);
+                    self.diagnose(i, DiagnosticKind::StackDepthExceeded);
+                    if end >= state.len() {
+                        self.diagnose(i, DiagnosticKind::UnclosedPush);
+                    }
+                    self.index = (end + 1).min(state.len());
+                    return;
+                }
+                let attachment = self.tip;
+                self.stack.push(StackFrame {
+                    turtle: self.turtle,
+                    path: self.path,
+                    attachment,
+                    blocked: self.blocked,
+                    width_growth: self.width_growth,
+                    push_index: i,
+                });
+                self.path = BranchPath::branch(key);
+                // Explicitly break the strand on Push to isolate the branch
+                self.end_strand();
+                self.sink.branch_push();
+                self.begin_strand(
+                    skeleton_point(&self.turtle, self.width_growth),
+                    strand_info(attachment, self.stack.len()),
+                );
+            }
+            TurtleOp::Pop => {
+                if let Some(frame) = self.stack.pop() {
+                    self.turtle = frame.turtle;
+                    self.path = frame.path;
+                    self.blocked = frame.blocked;
+                    self.width_growth = frame.width_growth;
+                    self.end_strand();
+                    self.sink.branch_pop();
+                    self.begin_strand(
+                        skeleton_point(&self.turtle, self.width_growth),
+                        strand_info(frame.attachment, self.stack.len()),
+                    );
+                } else {
+                    self.diagnose(i, DiagnosticKind::UnmatchedPop);
+                }
+            }
+            TurtleOp::Spawn(default_id) => {
+                if let Some(&value) = view.params.first()
+                    && finite
+                    && !fits_integer(value, u16::MAX)
+                {
+                    self.diagnose(i, DiagnosticKind::PropIdOutOfRange { value });
+                }
+                let prop_id = view
+                    .params
+                    .first()
+                    .map(|&x| x as u16)
+                    .unwrap_or(*default_id);
+                let scale_scalar = view.params.get(1).map(|&x| x as f32).unwrap_or(1.0)
+                    * growth(&interpreter.config.growth.prop_scale);
+                // A zero-scale prop (e.g. not grown yet) would only export degenerate instances
+                if scale_scalar == 0.0 {
+                    return;
+                }
+
+                self.spawn_prop(SkeletonProp {
+                    prop_id,
+                    position: self.turtle.position,
+                    rotation: self.turtle.rotation,
+                    scale: Vec3::splat(scale_scalar),
+                    color: self.turtle.color,
+                    material_id: self.turtle.material_id,
+                });
+            }
+            TurtleOp::BeginPolygon => {
+                self.polygon_stack.push(SkeletonPolygon {
+                    vertices: Vec::new(),
+                    color: self.turtle.color,
+                    material_id: self.turtle.material_id,
+                });
+            }
+            TurtleOp::EndPolygon => {
+                if let Some(polygon) = self.polygon_stack.pop()
+                    && polygon.vertices.len() >= 3
+                {
+                    self.add_polygon(polygon);
+                }
+            }
+            TurtleOp::RecordVertex => {
+                if let Some(polygon) = self.polygon_stack.last_mut() {
+                    polygon.vertices.push(self.turtle.position);
+                }
+            }
+            TurtleOp::Query(kind) => {
+                let value = match kind {
+                    QueryKind::Position => self.turtle.position,
+                    QueryKind::Heading => self.turtle.up(),
+                    QueryKind::Up => self.turtle.forward(),
+                };
+                self.patches.push(QueryPatch {
+                    module_index: i,
+                    kind: *kind,
+                    values: self.frame.output_vector(value).to_array().map(f64::from),
+                });
+            }
+            TurtleOp::Cut => {
+                // Resume at the closing `]`, which pops as usual; outside any branch a
+                // stray `]` must not end the cut
+                self.index = if self.stack.is_empty() {
+                    state.len()
+                } else {
+                    self.branch_end(self.index)
+                };
+            }
+            TurtleOp::Custom(id) => {
+                if let Some(Some(handler)) = interpreter.custom_ops.get(*id as usize) {
+                    // The turtle is copied out so the handler can borrow the sink adapter
+                    let mut turtle = self.turtle;
+                    handler(&mut turtle, view.params, &mut CustomOpSink { cursor: self });
+                    self.turtle = turtle;
+                }
+            }
+            TurtleOp::Ignore => {}
+        }
+    }
+}
+
+/// Sink handed to custom op handlers; keeps the cursor's strand bookkeeping in sync.
+struct CustomOpSink<'c, 'a, S: TurtleSink> {
+    cursor: &'c mut InterpretationCursor<'a, S>,
+}
+
+impl<S: TurtleSink> TurtleSink for CustomOpSink<'_, '_, S> {
+    fn begin_strand(&mut self, point: SkeletonPoint, info: StrandInfo) {
+        self.cursor.begin_strand(point, info);
+    }
+
+    fn add_point(&mut self, point: SkeletonPoint) {
+        self.cursor.add_point(point);
+    }
+
+    fn end_strand(&mut self) {
+        self.cursor.end_strand();
+    }
+
+    fn spawn_prop(&mut self, prop: SkeletonProp) {
+        self.cursor.spawn_prop(prop);
+    }
+
+    fn add_polygon(&mut self, polygon: SkeletonPolygon) {
+        self.cursor.add_polygon(polygon);
+    }
+
+    fn branch_push(&mut self) {
+        self.cursor.sink.branch_push();
+    }
+
+    fn branch_pop(&mut self) {
+        self.cursor.sink.branch_pop();
+    }
+
+    fn terminate_branch(&mut self, branch: TerminatedBranch) {
+        self.cursor.terminate_branch(branch);
+    }
+}
+
+impl<'a> InterpretationCursor<'a, Skeleton> {
+    /// The skeleton built so far.
+    ///
+    /// Post-processes such as
+    /// [`TurtleConfig::rotation_minimizing_frames`](crate::TurtleConfig::rotation_minimizing_frames)
+    /// are only applied by [`Self::finish`].
+    pub fn skeleton(&self) -> &Skeleton {
+        &self.sink
+    }
+
+    /// Interprets any remaining symbols and returns the finished skeleton.
+    pub fn finish(self) -> Skeleton {
+        self.finish_with_queries().0
+    }
+
+    /// Interprets any remaining symbols and returns the finished skeleton together
+    /// with the patches captured by [`TurtleOp::Query`] modules.
+    pub fn finish_with_queries(self) -> (Skeleton, Vec<QueryPatch>) {
+        let (skeleton, patches, _) = self.finish_skeleton();
+        (skeleton, patches)
+    }
+
+    /// Interprets any remaining symbols and returns the finished skeleton together
+    /// with the [`InterpretationReport`].
+    ///
+    /// Under [`TurtleConfig::strict`](crate::TurtleConfig::strict), interpretation
+    /// stops at the first problem and returns it as an error.
+    pub fn try_finish(mut self) -> Result<(Skeleton, InterpretationReport), InterpretError> {
+        let strict = self.interpreter.config.strict;
+        let strict_error = |report: &InterpretationReport| {
+            if strict {
+                report.first().map(InterpretError::Strict)
+            } else {
+                None
+            }
+        };
+        while !self.is_finished() {
+            self.advance(1);
+            if let Some(error) = strict_error(&self.report) {
+                return Err(error);
+            }
+        }
+        if let Some(module_index) = self.unreadable {
+            return Err(InterpretError::UnreadableModule { module_index });
+        }
+        let (skeleton, _, report) = self.finish_skeleton();
+        match strict_error(&report) {
+            Some(error) => Err(error),
+            None => Ok((skeleton, report)),
+        }
+    }
+
+    /// Finishes the interpretation and applies skeleton post-processes.
+    fn finish_skeleton(self) -> (Skeleton, Vec<QueryPatch>, InterpretationReport) {
+        let config = &self.interpreter.config;
+        let rotation_minimizing_frames = config.rotation_minimizing_frames;
+        let canonical_heading = config.conventions.heading == Axis::Y;
+        let frame = self.frame;
+        let (mut skeleton, patches, report) = self.finish_parts();
+        if rotation_minimizing_frames {
+            if canonical_heading {
+                skeleton.apply_rotation_minimizing_frames();
+            } else {
+                // Frames keep local +Y along the tangent, so compute them on canonical local axes
+                map_rotations(&mut skeleton, |r| frame.canonical_local(r));
+                skeleton.apply_rotation_minimizing_frames();
+                map_rotations(&mut skeleton, |r| frame.output_local(r));
+            }
+        }
+        (skeleton, patches, report)
+    }
+}
+
+/// Applies `f` to the rotation of every strand point.
+fn map_rotations(skeleton: &mut Skeleton, f: impl Fn(Quat) -> Quat) {
+    for point in skeleton.strands.iter_mut().flatten() {
+        point.rotation = f(point.rotation);
+    }
+}
+
+/// Presents an output-frame [`Environment`] in the canonical frame.
+struct FramedEnvironment<'e> {
+    inner: &'e dyn Environment,
+    frame: Frame,
+}
+
+impl Environment for FramedEnvironment<'_> {
+    fn signed_distance(&self, point: Vec3) -> f32 {
+        self.inner.signed_distance(self.frame.output_vector(point))
+    }
+
+    fn normal(&self, point: Vec3) -> Vec3 {
+        self.frame
+            .canonical_vector(self.inner.normal(self.frame.output_vector(point)))
+    }
+}
+
+/// Captures the turtle as a skeleton point, scaling its radius by `width_scale`.
+fn skeleton_point(turtle: &TurtleState, width_scale: f32) -> SkeletonPoint {
+    let mut point = turtle.to_point();
+    point.radius *= width_scale;
+    point
+}
+
+/// Returns `true` if `value` is an integer in `0..=max`, so casting it loses nothing.
+fn fits_integer(value: f64, max: impl Into<f64>) -> bool {
+    value.fract() == 0.0 && (0.0..=max.into()).contains(&value)
+}
+
+/// Builds the topology record for a strand attached at `(strand, point)`.
+fn strand_info(attachment: Option<(usize, usize)>, depth: usize) -> StrandInfo {
+    StrandInfo {
+        parent: attachment.map(|(strand, _)| strand),
+        parent_point: attachment.map_or(0, |(_, point)| point),
+        depth,
+        detached: false,
+    }
+}
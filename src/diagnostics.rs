@@ -0,0 +1,155 @@
+//! Interpretation diagnostics.
+//!
+//! [`TurtleInterpreter::build_skeleton`](crate::TurtleInterpreter::build_skeleton)
+//! tolerates malformed strings: a stray `]` is ignored, an angle that overflows `f32` produces NaN
+//! geometry and an unknown symbol does nothing. The [`InterpretationReport`] returned
+//! by [`TurtleInterpreter::try_build_skeleton`](crate::TurtleInterpreter::try_build_skeleton)
+//! lists each of these with the index of the module that caused it, and
+//! [`TurtleConfig::strict`](crate::TurtleConfig::strict) turns them into errors.
+
+use crate::limits::Limit;
+use std::error::Error;
+use std::fmt;
+
+/// What went wrong at a module.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiagnosticKind {
+    /// A `]` with no open branch; it was ignored.
+    UnmatchedPop,
+    /// A `[` never closed before the end of the string.
+    UnclosedPush,
+    /// A Push beyond [`TurtleConfig::max_stack_depth`](crate::TurtleConfig::max_stack_depth);
+    /// its subtree was skipped.
+    StackDepthExceeded,
+    /// The parameter at index `param` is NaN or infinite, or overflows `f32`.
+    NonFiniteParameter { param: usize },
+    /// SetWidth set a negative width.
+    NegativeWidth { width: f32 },
+    /// SetMaterial got a value that does not fit in a `u8` material ID.
+    MaterialOutOfRange { value: f64 },
+    /// Spawn got a value that does not fit in a `u16` prop ID.
+    PropIdOutOfRange { value: f64 },
+    /// A symbol with no op mapped; it was ignored. Reported once per symbol, at its
+    /// first occurrence.
+    UnmappedSymbol { symbol: u16 },
+    /// A [`ResourceLimits`](crate::ResourceLimits) cap was reached; interpretation
+    /// stopped before this module's output.
+    LimitExceeded { limit: Limit },
+    /// The [`CancellationToken`](crate::CancellationToken) was cancelled;
+    /// interpretation stopped before this module.
+    Cancelled,
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::UnmatchedPop => write!(f, "unmatched `]`"),
+            DiagnosticKind::UnclosedPush => write!(f, "unclosed `[`"),
+            DiagnosticKind::StackDepthExceeded => {
+                write!(f, "maximum stack depth exceeded, branch skipped")
+            }
+            DiagnosticKind::NonFiniteParameter { param } => {
+                write!(f, "parameter {param} is not finite")
+            }
+            DiagnosticKind::NegativeWidth { width } => write!(f, "negative width {width}"),
+            DiagnosticKind::MaterialOutOfRange { value } => {
+                write!(f, "material ID {value} is not an integer in 0..=255")
+            }
+            DiagnosticKind::PropIdOutOfRange { value } => {
+                write!(f, "prop ID {value} is not an integer in 0..=65535")
+            }
+            DiagnosticKind::UnmappedSymbol { symbol } => {
+                write!(f, "symbol {symbol} has no turtle op")
+            }
+            DiagnosticKind::LimitExceeded { limit } => {
+                write!(f, "{limit:?} limit exceeded, interpretation stopped")
+            }
+            DiagnosticKind::Cancelled => write!(f, "interpretation cancelled"),
+        }
+    }
+}
+
+/// A problem found while interpreting the module at `module_index`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub module_index: usize,
+    pub kind: DiagnosticKind,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "module {}: {}", self.module_index, self.kind)
+    }
+}
+
+/// Occurrences of a symbol with no op mapped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnmappedSymbol {
+    pub symbol: u16,
+    /// Index of the first module using the symbol.
+    pub first_index: usize,
+    /// Number of modules using the symbol.
+    pub count: usize,
+}
+
+/// Everything suspicious found during one interpretation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InterpretationReport {
+    /// Problems in the order they were found; unclosed `[` are reported last, unless
+    /// interpretation stopped early.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Unmapped symbols in order of first occurrence.
+    pub unmapped_symbols: Vec<UnmappedSymbol>,
+}
+
+impl InterpretationReport {
+    /// Returns `true` if nothing was reported.
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty() && self.unmapped_symbols.is_empty()
+    }
+
+    /// The limit or cancellation that stopped interpretation early, if any.
+    pub fn stopped(&self) -> Option<Diagnostic> {
+        self.diagnostics.iter().copied().find(|d| {
+            matches!(
+                d.kind,
+                DiagnosticKind::LimitExceeded { .. } | DiagnosticKind::Cancelled
+            )
+        })
+    }
+
+    /// The reported problem with the lowest module index, unmapped symbols included.
+    pub fn first(&self) -> Option<Diagnostic> {
+        let unmapped = self.unmapped_symbols.iter().map(|u| Diagnostic {
+            module_index: u.first_index,
+            kind: DiagnosticKind::UnmappedSymbol { symbol: u.symbol },
+        });
+        self.diagnostics
+            .iter()
+            .copied()
+            .chain(unmapped)
+            .min_by_key(|d| d.module_index)
+    }
+}
+
+/// Error returned by [`TurtleInterpreter::try_build_skeleton`](crate::TurtleInterpreter::try_build_skeleton).
+#[derive(Clone, Debug, PartialEq)]
+pub enum InterpretError {
+    /// Strict mode is on and the interpretation reported a problem.
+    Strict(Diagnostic),
+    /// The state could not produce the module at `module_index`.
+    UnreadableModule { module_index: usize },
+}
+
+impl fmt::Display for InterpretError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpretError::Strict(diagnostic) => write!(f, "strict interpretation: {diagnostic}"),
+            InterpretError::UnreadableModule { module_index } => {
+                write!(f, "module {module_index} could not be read from the state")
+            }
+        }
+    }
+}
+
+impl Error for InterpretError {}
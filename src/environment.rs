@@ -0,0 +1,82 @@
+//! Environment queries consulted during interpretation.
+//!
+//! An [`Environment`] describes obstacles as a signed distance field. Draw operations
+//! that would enter an obstacle are resolved according to the configured
+//! [`ObstaclePolicy`], so vines and ivy can grow against level geometry instead of
+//! clipping through it.
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Distance below which a point is considered to touch an obstacle surface.
+pub(crate) const SURFACE_EPSILON: f32 = 1e-4;
+
+/// Maximum sphere-tracing iterations per Draw before the segment is accepted as clear.
+const MAX_MARCH_STEPS: usize = 256;
+
+/// The world the turtle grows in.
+pub trait Environment: Send + Sync {
+    /// Signed distance from `point` to the nearest obstacle surface: positive outside,
+    /// negative inside.
+    ///
+    /// Should not overestimate the true distance, or thin obstacles may be skipped.
+    fn signed_distance(&self, point: Vec3) -> f32;
+
+    /// Outward unit surface normal near `point`.
+    ///
+    /// Defaults to central differences of [`Self::signed_distance`].
+    fn normal(&self, point: Vec3) -> Vec3 {
+        let h = 1e-3;
+        let gradient = Vec3::new(
+            self.signed_distance(point + Vec3::X * h) - self.signed_distance(point - Vec3::X * h),
+            self.signed_distance(point + Vec3::Y * h) - self.signed_distance(point - Vec3::Y * h),
+            self.signed_distance(point + Vec3::Z * h) - self.signed_distance(point - Vec3::Z * h),
+        );
+        gradient.normalize_or_zero()
+    }
+}
+
+/// How a Draw that would enter an obstacle is resolved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ObstaclePolicy {
+    /// Clip the segment at the surface and ignore the rest of the branch's Draw/Move
+    /// operations until its closing `]`.
+    #[default]
+    Stop,
+    /// Slide along the surface for the remaining length, bending the heading.
+    ///
+    /// A head-on contact leaves nothing to slide along, so it stops the branch as under
+    /// [`ObstaclePolicy::Stop`]; so does a step still unfinished after 8 slides.
+    Deflect,
+    /// Like [`ObstaclePolicy::Stop`], but also record a [`TerminatedBranch`] so the
+    /// next derivation can react.
+    Terminate,
+}
+
+/// A branch stopped by an obstacle under [`ObstaclePolicy::Terminate`].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TerminatedBranch {
+    /// Index of the Draw module that hit the obstacle.
+    pub module_index: usize,
+    /// Index of the strand that ends at the obstacle.
+    pub strand: usize,
+    /// World-space contact point.
+    pub position: Vec3,
+}
+
+/// Sphere-traces from `origin` along unit `dir` for up to `length`, returning the
+/// distance travelled before touching an obstacle, if any.
+pub(crate) fn march(env: &dyn Environment, origin: Vec3, dir: Vec3, length: f32) -> Option<f32> {
+    let mut t = 0.0;
+    for _ in 0..MAX_MARCH_STEPS {
+        let d = env.signed_distance(origin + dir * t);
+        if d < SURFACE_EPSILON {
+            return Some(t);
+        }
+        if t >= length {
+            return None;
+        }
+        t = (t + d).min(length);
+    }
+    None
+}
@@ -0,0 +1,75 @@
+//! Rotation-minimizing frames along skeleton strands.
+//!
+//! The raw turtle orientation twists abruptly wherever Roll ops occur between
+//! draws, which makes texture seams spiral around the mesh. Rotation-minimizing
+//! frames (computed with the double-reflection method of Wang et al., 2008) follow
+//! each strand with the least possible twist.
+
+use crate::conventions::Axis;
+use crate::skeleton::{Skeleton, strand_tangent};
+use glam::{Mat3, Quat, Vec3};
+
+impl Skeleton {
+    /// Replaces every [`SkeletonPoint::rotation`](crate::SkeletonPoint::rotation) with a
+    /// rotation-minimizing frame.
+    ///
+    /// Each strand's initial frame is the turtle frame at its first point, minimally
+    /// rotated so that its heading (local +Y) follows the strand tangent. Later frames
+    /// are propagated by double reflection, keeping local +Y along the tangent.
+    pub fn apply_rotation_minimizing_frames(&mut self) {
+        for strand in &mut self.strands {
+            if strand.len() < 2 {
+                continue;
+            }
+
+            let tangents: Vec<Vec3> = (0..strand.len())
+                .map(|i| strand_tangent(strand, i, Axis::Y))
+                .collect();
+
+            let first = strand[0].rotation;
+            let align = Quat::from_rotation_arc(first * Vec3::Y, tangents[0]);
+            let mut reference = (align * first) * Vec3::X;
+
+            for i in 0..strand.len() {
+                if i > 0 {
+                    reference = reflect_frame(
+                        strand[i].position - strand[i - 1].position,
+                        reference,
+                        tangents[i - 1],
+                        tangents[i],
+                    );
+                }
+                strand[i].rotation = frame_rotation(reference, tangents[i]);
+            }
+        }
+    }
+}
+
+/// Propagates `reference` from one sample to the next by double reflection.
+fn reflect_frame(step: Vec3, reference: Vec3, tangent: Vec3, next_tangent: Vec3) -> Vec3 {
+    let c1 = step.length_squared();
+    if c1 < 1e-12 {
+        return reference;
+    }
+    let r_l = reference - (2.0 / c1) * step.dot(reference) * step;
+    let t_l = tangent - (2.0 / c1) * step.dot(tangent) * step;
+
+    let v2 = next_tangent - t_l;
+    let c2 = v2.length_squared();
+    if c2 < 1e-12 {
+        return r_l;
+    }
+    r_l - (2.0 / c2) * v2.dot(r_l) * v2
+}
+
+/// Builds a rotation mapping local +X to `reference` and local +Y to `tangent`.
+fn frame_rotation(reference: Vec3, tangent: Vec3) -> Quat {
+    // Re-orthogonalize to stop numerical drift over long strands
+    let x = (reference - tangent * reference.dot(tangent)).normalize_or_zero();
+    let x = if x == Vec3::ZERO {
+        tangent.any_orthonormal_vector()
+    } else {
+        x
+    };
+    Quat::from_mat3(&Mat3::from_cols(x, tangent, x.cross(tangent))).normalize()
+}
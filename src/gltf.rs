@@ -0,0 +1,549 @@
+//! Binary glTF 2.0 (`.glb`) export.
+//!
+//! Writes a self-contained `.glb` from a [`Skeleton`]: the tube mesh from
+//! [`SkeletonMesh::build`] with one primitive per `material_id` and vertex colors,
+//! polygons as a second mesh, and one node per `prop_id` whose mesh is instanced at
+//! every prop's position, rotation and scale through `EXT_mesh_gpu_instancing`.
+//!
+//! With [`GltfConfig::joint_interval`] set, the strand mesh is exported skinned to a
+//! [`BoneHierarchy`] instead, so trees can be posed and animated in DCC tools.
+//!
+//! glTF is Y-up and expects meshes to extend along local +Y, which matches the default
+//! [`Conventions`](crate::Conventions).
+
+use crate::bones::BoneHierarchy;
+use crate::mesh::{MeshConfig, SkeletonMesh, SubMesh};
+use crate::skeleton::Skeleton;
+use glam::{Vec2, Vec3, Vec4};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+const TARGET_ARRAY_BUFFER: u32 = 34962;
+const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const COMPONENT_FLOAT: u32 = 5126;
+const COMPONENT_UNSIGNED_SHORT: u32 = 5123;
+const COMPONENT_UNSIGNED_INT: u32 = 5125;
+
+/// Configuration for glTF export.
+#[derive(Clone, Debug)]
+pub struct GltfConfig {
+    /// Tube mesh settings for strands.
+    pub mesh: MeshConfig,
+    /// Export polygons as a separate mesh.
+    pub polygons: bool,
+    /// Export props as instanced nodes.
+    pub props: bool,
+    /// Mesh instanced for each `prop_id`. Prop IDs without an entry get a unit quad
+    /// in the local XY plane, extending from the origin along +Y, in the material of
+    /// the first prop using the ID.
+    pub prop_meshes: BTreeMap<u16, SkeletonMesh>,
+    /// Skin the strand mesh to a [`BoneHierarchy`] with a joint every `n` points.
+    ///
+    /// Joints are exported as nodes carrying the strand points' rotations, under a
+    /// single `armature` node that is the skin's skeleton root. Every vertex is weighted
+    /// to the two joints around it on its strand. Polygons and props stay unskinned.
+    pub joint_interval: Option<usize>,
+}
+
+impl Default for GltfConfig {
+    fn default() -> Self {
+        Self {
+            mesh: MeshConfig::default(),
+            polygons: true,
+            props: true,
+            prop_meshes: BTreeMap::new(),
+            joint_interval: None,
+        }
+    }
+}
+
+/// Writes `skeleton` as a binary glTF to `out`.
+///
+/// Materials are named `material_<id>` with a white base color, so the vertex colors
+/// show through until the palette is bound; they are double-sided for polygons and
+/// prop cards.
+///
+/// Fails with [`io::ErrorKind::InvalidInput`] if a skin would need more than 65536
+/// joints; raise [`GltfConfig::joint_interval`] in that case.
+pub fn write_glb(skeleton: &Skeleton, config: &GltfConfig, out: &mut impl Write) -> io::Result<()> {
+    let mut builder = GltfBuilder::default();
+
+    match config.joint_interval {
+        Some(interval) => builder.add_skinned_strands(skeleton, &config.mesh, interval)?,
+        None => {
+            let tubes = SkeletonMesh::build(skeleton, &config.mesh);
+            if let Some(mesh) = builder.add_mesh("strands", &tubes) {
+                builder.add_node(json!({ "name": "strands", "mesh": mesh }), true);
+            }
+        }
+    }
+
+    if config.polygons
+        && let Some(mesh) = builder.add_mesh("polygons", &polygon_mesh(skeleton))
+    {
+        builder.add_node(json!({ "name": "polygons", "mesh": mesh }), true);
+    }
+
+    if config.props {
+        let mut instances: BTreeMap<u16, Vec<usize>> = BTreeMap::new();
+        for (index, prop) in skeleton.props.iter().enumerate() {
+            instances.entry(prop.prop_id).or_default().push(index);
+        }
+        for (prop_id, indices) in instances {
+            let name = format!("prop_{prop_id}");
+            let placeholder;
+            let prop_mesh = match config.prop_meshes.get(&prop_id) {
+                Some(mesh) => mesh,
+                None => {
+                    placeholder = prop_card(skeleton.props[indices[0]].material_id);
+                    &placeholder
+                }
+            };
+            let Some(mesh) = builder.add_mesh(&name, prop_mesh) else {
+                continue;
+            };
+
+            let props = indices.iter().map(|&i| &skeleton.props[i]);
+            let translations: Vec<f32> =
+                props.clone().flat_map(|p| p.position.to_array()).collect();
+            let rotations: Vec<f32> = props.clone().flat_map(|p| p.rotation.to_array()).collect();
+            let scales: Vec<f32> = props.flat_map(|p| p.scale.to_array()).collect();
+            let translation = builder.float_accessor(&translations, "VEC3", None, false);
+            let rotation = builder.float_accessor(&rotations, "VEC4", None, false);
+            let scale = builder.float_accessor(&scales, "VEC3", None, false);
+            let node = json!({
+                "name": name,
+                "mesh": mesh,
+                "extensions": {
+                    "EXT_mesh_gpu_instancing": {
+                        "attributes": {
+                            "TRANSLATION": translation,
+                            "ROTATION": rotation,
+                            "SCALE": scale,
+                        }
+                    }
+                }
+            });
+            builder.add_node(node, true);
+            builder.instancing = true;
+        }
+    }
+
+    builder.write(out)
+}
+
+/// Writes `skeleton` as a binary glTF file at `path`.
+pub fn save_glb(
+    skeleton: &Skeleton,
+    config: &GltfConfig,
+    path: impl AsRef<Path>,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_glb(skeleton, config, &mut out)?;
+    out.flush()
+}
+
+/// Collects polygons into a flat-shaded mesh with one submesh per material.
+fn polygon_mesh(skeleton: &Skeleton) -> SkeletonMesh {
+    let mut mesh = SkeletonMesh::default();
+    let mut submeshes: BTreeMap<u8, Vec<u32>> = BTreeMap::new();
+    for polygon in &skeleton.polygons {
+        let base = mesh.positions.len() as u32;
+        let normal = polygon.normal();
+        for vertex in &polygon.vertices {
+            mesh.positions.push(*vertex);
+            mesh.normals.push(normal);
+            mesh.uvs.push(Vec2::ZERO);
+            mesh.colors.push(polygon.color);
+            mesh.material_ids.push(polygon.material_id);
+        }
+        let indices = submeshes.entry(polygon.material_id).or_default();
+        for triangle in polygon.triangulate() {
+            indices.extend(triangle.map(|i| base + i));
+        }
+    }
+    mesh.submeshes = submeshes
+        .into_iter()
+        .map(|(material_id, indices)| SubMesh {
+            material_id,
+            indices,
+        })
+        .collect();
+    mesh
+}
+
+/// Unit quad used for props without a mesh in [`GltfConfig::prop_meshes`].
+fn prop_card(material_id: u8) -> SkeletonMesh {
+    let corners = [
+        Vec2::new(-0.5, 0.0),
+        Vec2::new(0.5, 0.0),
+        Vec2::new(0.5, 1.0),
+        Vec2::new(-0.5, 1.0),
+    ];
+    SkeletonMesh {
+        positions: corners.iter().map(|c| c.extend(0.0)).collect(),
+        normals: vec![Vec3::Z; 4],
+        uvs: corners
+            .iter()
+            .map(|c| Vec2::new(c.x + 0.5, 1.0 - c.y))
+            .collect(),
+        colors: vec![Vec4::ONE; 4],
+        material_ids: vec![material_id; 4],
+        submeshes: vec![SubMesh {
+            material_id,
+            indices: vec![0, 1, 2, 0, 2, 3],
+        }],
+    }
+}
+
+/// Accumulates the JSON document and binary buffer of a glTF asset.
+#[derive(Default)]
+struct GltfBuilder {
+    bin: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    meshes: Vec<Value>,
+    nodes: Vec<Value>,
+    /// Nodes listed in the scene; all others are children of another node.
+    roots: Vec<usize>,
+    skins: Vec<Value>,
+    /// Material IDs in glTF material order.
+    materials: Vec<u8>,
+    /// Whether any node uses `EXT_mesh_gpu_instancing`.
+    instancing: bool,
+}
+
+impl GltfBuilder {
+    /// Returns the glTF material index for `material_id`, adding it if needed.
+    fn material(&mut self, material_id: u8) -> usize {
+        match self.materials.iter().position(|&id| id == material_id) {
+            Some(index) => index,
+            None => {
+                self.materials.push(material_id);
+                self.materials.len() - 1
+            }
+        }
+    }
+
+    /// Adds `node` and returns its index; `root` nodes are listed in the scene.
+    fn add_node(&mut self, node: Value, root: bool) -> usize {
+        self.nodes.push(node);
+        let index = self.nodes.len() - 1;
+        if root {
+            self.roots.push(index);
+        }
+        index
+    }
+
+    /// Appends `bytes` to the buffer as a new buffer view and returns its index.
+    fn buffer_view(&mut self, bytes: &[u8], target: Option<u32>) -> usize {
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.bin.len(),
+            "byteLength": bytes.len(),
+        });
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+        self.bin.extend_from_slice(bytes);
+        // Every element is a multiple of four bytes wide, so views stay aligned
+        self.buffer_views.push(view);
+        self.buffer_views.len() - 1
+    }
+
+    /// Adds an accessor over `data`, grouped into elements of type `kind`, and
+    /// returns its index. `bounds` records per-component min/max (required for POSITION).
+    fn float_accessor(
+        &mut self,
+        data: &[f32],
+        kind: &str,
+        target: Option<u32>,
+        bounds: bool,
+    ) -> usize {
+        let width = component_count(kind);
+        let bytes: Vec<u8> = data.iter().flat_map(|x| x.to_le_bytes()).collect();
+        let view = self.buffer_view(&bytes, target);
+        let mut accessor = json!({
+            "bufferView": view,
+            "componentType": COMPONENT_FLOAT,
+            "count": data.len() / width,
+            "type": kind,
+        });
+        if bounds {
+            let mut min = vec![f32::INFINITY; width];
+            let mut max = vec![f32::NEG_INFINITY; width];
+            for element in data.chunks_exact(width) {
+                for (c, &x) in element.iter().enumerate() {
+                    min[c] = min[c].min(x);
+                    max[c] = max[c].max(x);
+                }
+            }
+            accessor["min"] = json!(min);
+            accessor["max"] = json!(max);
+        }
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    /// Adds an accessor over unsigned integer `data` of type `kind`.
+    fn uint_accessor(&mut self, data: &[u32], kind: &str, target: Option<u32>) -> usize {
+        let bytes: Vec<u8> = data.iter().flat_map(|x| x.to_le_bytes()).collect();
+        let view = self.buffer_view(&bytes, target);
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": COMPONENT_UNSIGNED_INT,
+            "count": data.len() / component_count(kind),
+            "type": kind,
+        }));
+        self.accessors.len() - 1
+    }
+
+    /// Adds an accessor over unsigned short `data` of type `kind`.
+    fn ushort_accessor(&mut self, data: &[u16], kind: &str, target: Option<u32>) -> usize {
+        let bytes: Vec<u8> = data.iter().flat_map(|x| x.to_le_bytes()).collect();
+        let view = self.buffer_view(&bytes, target);
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": COMPONENT_UNSIGNED_SHORT,
+            "count": data.len() / component_count(kind),
+            "type": kind,
+        }));
+        self.accessors.len() - 1
+    }
+
+    /// Adds the vertex attributes of `mesh` and returns them as a primitive attribute map.
+    fn vertex_attributes(&mut self, mesh: &SkeletonMesh) -> Value {
+        let positions: Vec<f32> = mesh.positions.iter().flat_map(|v| v.to_array()).collect();
+        let normals: Vec<f32> = mesh.normals.iter().flat_map(|v| v.to_array()).collect();
+        let uvs: Vec<f32> = mesh.uvs.iter().flat_map(|v| v.to_array()).collect();
+        let colors: Vec<f32> = mesh.colors.iter().flat_map(|v| v.to_array()).collect();
+        let array = Some(TARGET_ARRAY_BUFFER);
+        json!({
+            "POSITION": self.float_accessor(&positions, "VEC3", array, true),
+            "NORMAL": self.float_accessor(&normals, "VEC3", array, false),
+            "TEXCOORD_0": self.float_accessor(&uvs, "VEC2", array, false),
+            "COLOR_0": self.float_accessor(&colors, "VEC4", array, false),
+        })
+    }
+
+    /// Adds one primitive per submesh of `mesh`, all sharing `attributes`, and returns
+    /// the primitives.
+    fn primitives(&mut self, mesh: &SkeletonMesh, attributes: &Value) -> Vec<Value> {
+        let mut primitives = Vec::new();
+        for submesh in &mesh.submeshes {
+            if submesh.indices.is_empty() {
+                continue;
+            }
+            let indices = self.uint_accessor(
+                &submesh.indices,
+                "SCALAR",
+                Some(TARGET_ELEMENT_ARRAY_BUFFER),
+            );
+            primitives.push(json!({
+                "attributes": attributes,
+                "indices": indices,
+                "material": self.material(submesh.material_id),
+            }));
+        }
+        primitives
+    }
+
+    /// Adds `mesh` with one primitive per submesh and returns its index, or `None` if it
+    /// has no triangles.
+    fn add_mesh(&mut self, name: &str, mesh: &SkeletonMesh) -> Option<usize> {
+        if mesh.triangle_count() == 0 {
+            return None;
+        }
+        let attributes = self.vertex_attributes(mesh);
+        let primitives = self.primitives(mesh, &attributes);
+        self.meshes
+            .push(json!({ "name": name, "primitives": primitives }));
+        Some(self.meshes.len() - 1)
+    }
+
+    /// Adds the strand mesh skinned to a [`BoneHierarchy`] with a joint every
+    /// `interval` points, the joint nodes and the skin.
+    fn add_skinned_strands(
+        &mut self,
+        skeleton: &Skeleton,
+        mesh_config: &MeshConfig,
+        interval: usize,
+    ) -> io::Result<()> {
+        let (tubes, sources) = SkeletonMesh::build_with_sources(skeleton, mesh_config);
+        if tubes.triangle_count() == 0 {
+            return Ok(());
+        }
+        let bones = BoneHierarchy::from_skeleton(skeleton, interval);
+        if bones.joints.len() > usize::from(u16::MAX) + 1 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "skin needs more than 65536 joints; increase the joint interval",
+            ));
+        }
+
+        // Root joints share one armature node so the skin has a common root
+        let armature = self.add_node(json!({ "name": "armature" }), true);
+        let mut root_joints = Vec::new();
+
+        // Joint nodes, with rest transforms relative to their parent joint
+        let first_joint = self.nodes.len();
+        let mut children = vec![Vec::new(); bones.joints.len()];
+        for (index, joint) in bones.joints.iter().enumerate() {
+            let (translation, rotation) = bones.local_transform(index);
+            let node = json!({
+                "name": format!("joint_{index}"),
+                "translation": translation.to_array(),
+                "rotation": rotation.to_array(),
+            });
+            self.add_node(node, false);
+            match joint.parent {
+                Some(parent) => children[parent].push(first_joint + index),
+                None => root_joints.push(first_joint + index),
+            }
+        }
+        self.nodes[armature]["children"] = json!(root_joints);
+        for (index, children) in children.into_iter().enumerate() {
+            if !children.is_empty() {
+                self.nodes[first_joint + index]["children"] = json!(children);
+            }
+        }
+
+        let inverse_binds: Vec<f32> = bones
+            .joints
+            .iter()
+            .flat_map(|joint| joint.world_matrix().inverse().to_cols_array())
+            .collect();
+        let inverse_binds = self.float_accessor(&inverse_binds, "MAT4", None, false);
+        self.skins.push(json!({
+            "joints": (first_joint..first_joint + bones.joints.len()).collect::<Vec<_>>(),
+            "inverseBindMatrices": inverse_binds,
+            "skeleton": armature,
+        }));
+        let skin = self.skins.len() - 1;
+
+        // Influences are per strand point; vertices look them up through their source point
+        let influences: Vec<Vec<[(usize, f32); 2]>> = skeleton
+            .strands
+            .iter()
+            .enumerate()
+            .map(|(strand, points)| {
+                (0..points.len())
+                    .map(|point| {
+                        bones
+                            .influences(skeleton, strand, point)
+                            .unwrap_or([(0, 1.0), (0, 0.0)])
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut joints = Vec::with_capacity(sources.len() * 4);
+        let mut weights = Vec::with_capacity(sources.len() * 4);
+        for &(strand, point) in &sources {
+            let [(a, wa), (b, wb)] = influences[strand][point];
+            // Unused influences must have joint 0 and weight 0
+            let b = if wb > 0.0 { b } else { 0 };
+            joints.extend([a as u16, b as u16, 0, 0]);
+            weights.extend([wa, wb, 0.0, 0.0]);
+        }
+
+        let mut attributes = self.vertex_attributes(&tubes);
+        let array = Some(TARGET_ARRAY_BUFFER);
+        attributes["JOINTS_0"] = json!(self.ushort_accessor(&joints, "VEC4", array));
+        attributes["WEIGHTS_0"] = json!(self.float_accessor(&weights, "VEC4", array, false));
+        let primitives = self.primitives(&tubes, &attributes);
+        self.meshes
+            .push(json!({ "name": "strands", "primitives": primitives }));
+        let mesh = self.meshes.len() - 1;
+        self.add_node(
+            json!({ "name": "strands", "mesh": mesh, "skin": skin }),
+            true,
+        );
+        Ok(())
+    }
+
+    /// Builds the JSON document.
+    fn document(&self) -> Value {
+        let materials: Vec<Value> = self
+            .materials
+            .iter()
+            .map(|id| {
+                json!({
+                    "name": format!("material_{id}"),
+                    "pbrMetallicRoughness": { "baseColorFactor": [1.0, 1.0, 1.0, 1.0] },
+                    "doubleSided": true,
+                })
+            })
+            .collect();
+        let mut document = json!({
+            "asset": { "version": "2.0", "generator": "symbios-turtle-3d" },
+            "nodes": self.nodes,
+            "meshes": self.meshes,
+            "materials": materials,
+            "accessors": self.accessors,
+            "bufferViews": self.buffer_views,
+            "skins": self.skins,
+        });
+        if !self.roots.is_empty() {
+            document["scene"] = json!(0);
+            document["scenes"] = json!([{ "nodes": self.roots }]);
+        }
+        if !self.bin.is_empty() {
+            document["buffers"] = json!([{ "byteLength": self.bin.len() }]);
+        }
+        if self.instancing {
+            document["extensionsUsed"] = json!(["EXT_mesh_gpu_instancing"]);
+        }
+        // Empty arrays are not allowed by the schema
+        if let Some(document) = document.as_object_mut() {
+            document.retain(|_, value| !matches!(value, Value::Array(a) if a.is_empty()));
+        }
+        document
+    }
+
+    /// Writes the asset as a GLB container.
+    fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut json = serde_json::to_vec(&self.document()).map_err(io::Error::other)?;
+        json.resize(json.len().next_multiple_of(4), b' ');
+        let mut bin = self.bin.clone();
+        bin.resize(bin.len().next_multiple_of(4), 0);
+
+        let has_bin = !bin.is_empty();
+        let mut length = 12 + 8 + json.len();
+        if has_bin {
+            length += 8 + bin.len();
+        }
+        let length = u32::try_from(length)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "glTF asset exceeds 4 GiB"))?;
+
+        out.write_all(&GLB_MAGIC.to_le_bytes())?;
+        out.write_all(&2u32.to_le_bytes())?;
+        out.write_all(&length.to_le_bytes())?;
+        out.write_all(&(json.len() as u32).to_le_bytes())?;
+        out.write_all(&CHUNK_JSON.to_le_bytes())?;
+        out.write_all(&json)?;
+        if has_bin {
+            out.write_all(&(bin.len() as u32).to_le_bytes())?;
+            out.write_all(&CHUNK_BIN.to_le_bytes())?;
+            out.write_all(&bin)?;
+        }
+        Ok(())
+    }
+}
+
+fn component_count(kind: &str) -> usize {
+    match kind {
+        "SCALAR" => 1,
+        "VEC2" => 2,
+        "VEC3" => 3,
+        "VEC4" | "MAT2" => 4,
+        "MAT3" => 9,
+        "MAT4" => 16,
+        _ => 1,
+    }
+}
@@ -0,0 +1,102 @@
+//! Growth curves for age-driven animation.
+//!
+//! [`TurtleInterpreter::build_skeleton_at`](crate::TurtleInterpreter::build_skeleton_at)
+//! evaluates these curves from each module's age at the requested time, so a single
+//! derived string can be played back as continuous growth instead of popping between
+//! generations.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// Maps a module's age to a growth factor, usually in `[0, 1]`.
+///
+/// [`GrowthCurve::Custom`] cannot be serialized; serializing a config that uses it
+/// fails.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum GrowthCurve {
+    /// Grows linearly from `0` at birth to `1` after `duration`.
+    Linear { duration: f32 },
+    /// Logistic ease-in/ease-out from `0` at birth to `1` after `duration`.
+    ///
+    /// Higher `steepness` concentrates growth around `duration / 2`.
+    Sigmoid { duration: f32, steepness: f32 },
+    /// User-supplied function of age.
+    #[serde(skip)]
+    Custom(Arc<dyn Fn(f32) -> f32 + Send + Sync>),
+}
+
+impl GrowthCurve {
+    /// Returns the growth factor for a module of the given `age`.
+    ///
+    /// Modules not yet born (negative age) evaluate to `0` for the built-in curves.
+    pub fn evaluate(&self, age: f32) -> f32 {
+        match self {
+            GrowthCurve::Linear { duration } => {
+                if *duration <= 0.0 {
+                    return if age >= 0.0 { 1.0 } else { 0.0 };
+                }
+                (age / duration).clamp(0.0, 1.0)
+            }
+            GrowthCurve::Sigmoid {
+                duration,
+                steepness,
+            } => {
+                if *duration <= 0.0 {
+                    return if age >= 0.0 { 1.0 } else { 0.0 };
+                }
+                let logistic = |x: f32| 1.0 / (1.0 + (-steepness * (x - 0.5)).exp());
+                // Rescale so the curve starts exactly at 0 and ends exactly at 1
+                let (lo, hi) = (logistic(0.0), logistic(1.0));
+                if hi - lo <= f32::EPSILON {
+                    return (age / duration).clamp(0.0, 1.0);
+                }
+                let x = (age / duration).clamp(0.0, 1.0);
+                ((logistic(x) - lo) / (hi - lo)).clamp(0.0, 1.0)
+            }
+            GrowthCurve::Custom(f) => f(age),
+        }
+    }
+}
+
+impl Default for GrowthCurve {
+    fn default() -> Self {
+        GrowthCurve::Linear { duration: 1.0 }
+    }
+}
+
+impl fmt::Debug for GrowthCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrowthCurve::Linear { duration } => f
+                .debug_struct("Linear")
+                .field("duration", duration)
+                .finish(),
+            GrowthCurve::Sigmoid {
+                duration,
+                steepness,
+            } => f
+                .debug_struct("Sigmoid")
+                .field("duration", duration)
+                .field("steepness", steepness)
+                .finish(),
+            GrowthCurve::Custom(_) => f.debug_tuple("Custom").finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Growth curves applied by
+/// [`TurtleInterpreter::build_skeleton_at`](crate::TurtleInterpreter::build_skeleton_at).
+///
+/// Each curve is evaluated from the age of the module being interpreted: the Draw
+/// module for lengths and widths, the Spawn module for prop scales.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrowthConfig {
+    /// Scales Draw lengths.
+    pub length: GrowthCurve,
+    /// Scales the radius of the points a Draw produces.
+    pub width: GrowthCurve,
+    /// Scales spawned props. Props scaled to 0 are not spawned.
+    pub prop_scale: GrowthCurve,
+}
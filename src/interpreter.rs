@@ -1,29 +1,105 @@
 //! Interpreter that converts L-System symbols into 3D turtle movements.
 
-use crate::skeleton::{Skeleton, SkeletonPoint};
+use crate::conventions::Conventions;
+use crate::cursor::InterpretationCursor;
+use crate::diagnostics::{InterpretError, InterpretationReport};
+use crate::environment::{Environment, ObstaclePolicy};
+use crate::growth::GrowthConfig;
+use crate::jitter::JitterConfig;
+use crate::limits::{CancellationToken, ProgressCallback, ResourceLimits};
+use crate::query::{QueryKind, QueryPatch};
+use crate::sink::TurtleSink;
+use crate::skeleton::Skeleton;
+use crate::tropism::Tropism;
 use crate::turtle::{TurtleOp, TurtleState};
-use glam::{Mat3, Quat, Vec3, Vec4};
-use std::f32::consts::PI;
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use symbios::{SymbiosState, SymbolTable};
 
+/// Unit of angle parameters passed to Yaw/Pitch/Roll symbols, e.g. `+(30)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AngleUnit {
+    #[default]
+    Degrees,
+    Radians,
+}
+
+impl AngleUnit {
+    /// Converts `value`, expressed in this unit, to radians.
+    pub fn to_radians(self, value: f32) -> f32 {
+        match self {
+            AngleUnit::Degrees => value.to_radians(),
+            AngleUnit::Radians => value,
+        }
+    }
+}
+
 /// Configuration for turtle interpretation.
-#[derive(Clone, Debug)]
+///
+/// Serializable so it can be stored in an
+/// [`InterpretationProfile`](crate::InterpretationProfile); missing fields take their
+/// default values. [`Tropism::Field`] terms and
+/// [`GrowthCurve::Custom`](crate::GrowthCurve::Custom) curves hold closures and cannot
+/// be serialized.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TurtleConfig {
     /// Default step length for Draw/Move when no parameter is given.
     pub default_step: f32,
     /// Default rotation angle (in radians) for Yaw/Pitch/Roll.
     pub default_angle: f32,
+    /// Unit of explicit Yaw/Pitch/Roll angle parameters.
+    pub angle_unit: AngleUnit,
     /// Initial stroke width.
     pub initial_width: f32,
     /// Optional tropism vector (e.g., gravity direction for plant growth).
+    ///
+    /// This is the initial per-branch tropism; the grammar can override it with
+    /// [`TurtleOp::SetTropism`], and Push/Pop save and restore it.
     pub tropism: Option<Vec3>,
     /// Tropism elasticity - how strongly the turtle bends toward tropism vector.
+    ///
+    /// Initial per-branch value; see [`TurtleOp::SetElasticity`].
     pub elasticity: f32,
+    /// Additional tropism terms applied to every branch after each Draw
+    /// (e.g., phototropism toward a light alongside gravity).
+    pub tropisms: Vec<Tropism>,
     /// Maximum stack depth for push/pop operations.
     ///
     /// Prevents denial-of-service via infinite recursion (e.g., `A -> [ A ]`).
-    /// Push operations are ignored when this limit is reached.
+    /// A Push that would exceed this limit skips its entire bracketed subtree,
+    /// including the matching Pop.
     pub max_stack_depth: usize,
+    /// Optional seeded jitter for rotations, step lengths and widths.
+    ///
+    /// Samples are derived from the branch path and symbol index, so the result
+    /// is deterministic for a given seed and stable under edits to other branches.
+    pub jitter: Option<JitterConfig>,
+    /// Replace the raw turtle orientation of every skeleton point with a
+    /// rotation-minimizing frame (see [`Skeleton::apply_rotation_minimizing_frames`]).
+    ///
+    /// Prevents the abrupt twists that Roll ops introduce between draws.
+    pub rotation_minimizing_frames: bool,
+    /// Output coordinate frame: heading axis, world up and rotation sense.
+    ///
+    /// Tropism vectors, environments and query values use the configured world frame.
+    pub conventions: Conventions,
+    /// Growth curves used by [`TurtleInterpreter::build_skeleton_at`].
+    pub growth: GrowthConfig,
+    /// How Draw operations that would enter an obstacle are resolved.
+    ///
+    /// Only consulted when an [`Environment`] is attached with
+    /// [`TurtleInterpreter::with_environment`].
+    pub obstacle_policy: ObstaclePolicy,
+    /// Caps on emitted strands, points, props and drawn length.
+    ///
+    /// Interpretation stops cleanly at the first limit hit, keeping the output built so
+    /// far and reporting [`DiagnosticKind::LimitExceeded`](crate::DiagnosticKind::LimitExceeded).
+    pub limits: ResourceLimits,
+    /// Makes [`TurtleInterpreter::try_build_skeleton`] fail on the first diagnostic
+    /// instead of reporting it. Infallible builds are unaffected.
+    pub strict: bool,
 }
 
 impl Default for TurtleConfig {
@@ -31,76 +107,162 @@ impl Default for TurtleConfig {
         Self {
             default_step: 1.0,
             default_angle: 45.0f32.to_radians(),
+            angle_unit: AngleUnit::Degrees,
             initial_width: 0.1,
             tropism: None,
             elasticity: 0.0,
+            tropisms: Vec::new(),
             max_stack_depth: 1024,
+            jitter: None,
+            rotation_minimizing_frames: false,
+            conventions: Conventions::default(),
+            growth: GrowthConfig::default(),
+            obstacle_policy: ObstaclePolicy::Stop,
+            limits: ResourceLimits::default(),
+            strict: false,
         }
     }
 }
 
+/// Symbol mapping installed by [`TurtleInterpreter::populate_standard_symbols`].
+pub(crate) const STANDARD_SYMBOLS: &[(&str, TurtleOp)] = &[
+    ("F", TurtleOp::Draw),
+    ("f", TurtleOp::Move),
+    ("+", TurtleOp::Yaw(1.0)),
+    ("-", TurtleOp::Yaw(-1.0)),
+    ("&", TurtleOp::Pitch(1.0)),
+    ("^", TurtleOp::Pitch(-1.0)),
+    ("\\", TurtleOp::Roll(1.0)),
+    ("/", TurtleOp::Roll(-1.0)),
+    ("|", TurtleOp::TurnAround),
+    ("$", TurtleOp::Vertical),
+    ("!", TurtleOp::SetWidth),
+    ("[", TurtleOp::Push),
+    ("]", TurtleOp::Pop),
+    ("%", TurtleOp::Cut),
+    ("~", TurtleOp::Spawn(0)),
+    // Material Mappings
+    ("'", TurtleOp::SetColor),
+    (",", TurtleOp::SetMaterial),
+    (";", TurtleOp::SetUVScale),
+    // Polygon Mappings
+    ("{", TurtleOp::BeginPolygon),
+    ("}", TurtleOp::EndPolygon),
+    (".", TurtleOp::RecordVertex),
+    // Query Mappings
+    ("?P", TurtleOp::Query(QueryKind::Position)),
+    ("?H", TurtleOp::Query(QueryKind::Heading)),
+    ("?U", TurtleOp::Query(QueryKind::Up)),
+];
+
 /// Interprets L-System output as 3D turtle graphics, producing a [`Skeleton`].
 ///
 /// Maps symbol IDs to [`TurtleOp`]s and executes them to build geometry.
 /// Uses a Vec for O(1) direct lookup by symbol ID instead of hashing.
 pub struct TurtleInterpreter {
-    op_map: Vec<TurtleOp>,
-    config: TurtleConfig,
+    /// `None` marks symbols that were never mapped, which are reported as diagnostics.
+    pub(crate) op_map: Vec<Option<TurtleOp>>,
+    pub(crate) config: TurtleConfig,
+    pub(crate) environment: Option<Arc<dyn Environment>>,
+    pub(crate) custom_ops: Vec<Option<CustomOpHandler>>,
+    pub(crate) cancellation: Option<CancellationToken>,
+    pub(crate) progress: Option<ProgressCallback>,
 }
 
+/// Handler for [`TurtleOp::Custom`], registered with
+/// [`TurtleInterpreter::register_custom_op`].
+///
+/// Receives the turtle state (changes persist and are saved/restored by Push/Pop like
+/// any built-in op), the module's parameters and the output sink. Points and strands
+/// emitted through the sink take part in the interpreter's topology bookkeeping.
+///
+/// The turtle is presented in the interpreter's canonical frame (heading local +Y,
+/// world up +Y); everything emitted through the sink is converted to
+/// [`TurtleConfig::conventions`] like built-in output.
+pub type CustomOpHandler = Arc<dyn Fn(&mut TurtleState, &[f64], &mut dyn TurtleSink) + Send + Sync>;
+
 impl TurtleInterpreter {
     /// Creates a new interpreter with the given configuration.
     pub fn new(config: TurtleConfig) -> Self {
         Self {
             op_map: Vec::new(),
             config,
+            environment: None,
+            custom_ops: Vec::new(),
+            cancellation: None,
+            progress: None,
         }
     }
 
     /// Builder method to set the operation map from a Vec.
     pub fn with_map(mut self, map: Vec<TurtleOp>) -> Self {
-        self.op_map = map;
+        self.op_map = map.into_iter().map(Some).collect();
+        self
+    }
+
+    /// Builder method to attach an [`Environment`] whose obstacles constrain growth.
+    ///
+    /// Draw operations are clipped against the environment and resolved with
+    /// [`TurtleConfig::obstacle_policy`].
+    pub fn with_environment(mut self, environment: Arc<dyn Environment>) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Builder method to attach a [`CancellationToken`].
+    ///
+    /// Every interpretation polls the token every few hundred symbols and stops cleanly
+    /// once it is cancelled, reporting [`DiagnosticKind::Cancelled`](crate::DiagnosticKind::Cancelled).
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Builder method to register a progress callback, called every few hundred symbols
+    /// with the fraction of the string interpreted so far.
+    pub fn with_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(f32) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(callback));
         self
     }
 
     /// Maps a symbol ID to a turtle operation.
     ///
-    /// Grows the internal Vec as needed, filling gaps with `TurtleOp::Ignore`.
+    /// Grows the internal Vec as needed. Symbols in the gaps stay unmapped: they are
+    /// ignored like `TurtleOp::Ignore`, but reported in the [`InterpretationReport`].
+    /// Map a symbol to `TurtleOp::Ignore` explicitly to silence it.
     pub fn set_op(&mut self, sym_id: u16, op: TurtleOp) {
         let idx = sym_id as usize;
         if idx >= self.op_map.len() {
-            self.op_map.resize(idx + 1, TurtleOp::Ignore);
+            self.op_map.resize(idx + 1, None);
+        }
+        self.op_map[idx] = Some(op);
+    }
+
+    /// Registers the handler run by [`TurtleOp::Custom`] ops with the given `id`,
+    /// replacing any previous handler.
+    ///
+    /// Map symbols to the op with [`Self::set_op`].
+    pub fn register_custom_op<F>(&mut self, id: u16, handler: F)
+    where
+        F: Fn(&mut TurtleState, &[f64], &mut dyn TurtleSink) + Send + Sync + 'static,
+    {
+        let idx = id as usize;
+        if idx >= self.custom_ops.len() {
+            self.custom_ops.resize(idx + 1, None);
         }
-        self.op_map[idx] = op;
+        self.custom_ops[idx] = Some(Arc::new(handler));
     }
 
     /// Populates the operation map with standard L-System symbols from a symbol table.
     ///
-    /// Maps: `F`, `f`, `+`, `-`, `&`, `^`, `\`, `/`, `|`, `$`, `!`, `[`, `]`, `~`,
-    /// and material symbols: `'`, `,`, `;`.
+    /// Maps: `F`, `f`, `+`, `-`, `&`, `^`, `\`, `/`, `|`, `$`, `!`, `[`, `]`, `%`, `~`,
+    /// material symbols: `'`, `,`, `;`, polygon symbols: `{`, `}`, `.`, and query
+    /// symbols: `?P`, `?H`, `?U`.
     pub fn populate_standard_symbols(&mut self, interner: &SymbolTable) {
-        let mappings = [
-            ("F", TurtleOp::Draw),
-            ("f", TurtleOp::Move),
-            ("+", TurtleOp::Yaw(1.0)),
-            ("-", TurtleOp::Yaw(-1.0)),
-            ("&", TurtleOp::Pitch(1.0)),
-            ("^", TurtleOp::Pitch(-1.0)),
-            ("\\", TurtleOp::Roll(1.0)),
-            ("/", TurtleOp::Roll(-1.0)),
-            ("|", TurtleOp::TurnAround),
-            ("$", TurtleOp::Vertical),
-            ("!", TurtleOp::SetWidth),
-            ("[", TurtleOp::Push),
-            ("]", TurtleOp::Pop),
-            ("~", TurtleOp::Spawn(0)),
-            // Material Mappings
-            ("'", TurtleOp::SetColor),
-            (",", TurtleOp::SetMaterial),
-            (";", TurtleOp::SetUVScale),
-        ];
-
-        for (sym, op) in mappings {
+        for &(sym, op) in STANDARD_SYMBOLS {
             if let Some(id) = interner.resolve_id(sym) {
                 self.set_op(id, op);
             }
@@ -112,180 +274,81 @@ impl TurtleInterpreter {
     /// Iterates through all symbols in the state, executing the corresponding
     /// turtle operations and accumulating geometry into the skeleton.
     pub fn build_skeleton(&self, state: &SymbiosState) -> Skeleton {
-        let mut skeleton = Skeleton::new();
-        let mut turtle = TurtleState {
-            width: self.config.initial_width,
-            ..Default::default()
-        };
-        let mut stack = Vec::new();
+        self.build_skeleton_with_queries(state).0
+    }
 
-        for i in 0..state.len() {
-            let view = match state.get_view(i) {
-                Some(v) => v,
-                None => break,
-            };
+    /// Builds a [`Skeleton`] and reports malformed input: unbalanced brackets,
+    /// non-finite or out-of-range parameters, stack-depth truncations and unmapped
+    /// symbols, each tagged with its module index.
+    ///
+    /// With [`TurtleConfig::strict`] the first problem is returned as an error instead.
+    pub fn try_build_skeleton(
+        &self,
+        state: &SymbiosState,
+    ) -> Result<(Skeleton, InterpretationReport), InterpretError> {
+        self.cursor(state).try_finish()
+    }
 
-            let op = self
-                .op_map
-                .get(view.sym as usize)
-                .unwrap_or(&TurtleOp::Ignore);
-            // Helper to get param at index with default
-            let p = |idx: usize, def: f32| -> f32 {
-                view.params.get(idx).map(|&x| x as f32).unwrap_or(def)
-            };
-            let p0 = p(0, 0.0); // Common case helper
-            let get_val =
-                |default: f32| -> f32 { view.params.first().map(|&x| x as f32).unwrap_or(default) };
+    /// Builds a [`Skeleton`] and also returns the turtle state captured by every
+    /// [`TurtleOp::Query`] module.
+    ///
+    /// Pass the patches to [`apply_query_patches`](crate::query::apply_query_patches)
+    /// to write them into the state before the next derivation step (open L-systems).
+    pub fn build_skeleton_with_queries(&self, state: &SymbiosState) -> (Skeleton, Vec<QueryPatch>) {
+        self.cursor(state).finish_with_queries()
+    }
 
-            match op {
-                TurtleOp::Draw | TurtleOp::Move => {
-                    let len = get_val(self.config.default_step);
-                    let is_move = matches!(op, TurtleOp::Move);
+    /// Builds a [`Skeleton`] as it looked at `time`, for growth animation.
+    ///
+    /// Each module's age at `time` is its age in `state` offset by
+    /// `time - state.current_time`. Draw lengths, the radii of the points they produce
+    /// and prop scales are multiplied by the [`TurtleConfig::growth`] curves evaluated
+    /// at that age, so playing `time` forward animates a single derived string.
+    pub fn build_skeleton_at(&self, state: &SymbiosState, time: f64) -> Skeleton {
+        self.cursor_at(state, time).finish()
+    }
 
-                    if skeleton.strands.is_empty() {
-                        skeleton.add_node(
-                            SkeletonPoint {
-                                position: turtle.position,
-                                rotation: turtle.rotation,
-                                radius: turtle.width / 2.0,
-                                color: turtle.color,
-                                material_id: turtle.material_id,
-                                uv_scale: turtle.uv_scale,
-                            },
-                            true,
-                        );
-                    }
+    /// Starts a resumable interpretation of `state`.
+    ///
+    /// Use this instead of [`Self::build_skeleton`] to spread the work over several
+    /// calls, e.g. one chunk per frame.
+    pub fn cursor<'a>(&'a self, state: &'a SymbiosState) -> InterpretationCursor<'a> {
+        InterpretationCursor::new(self, state, None, self.empty_skeleton())
+    }
 
-                    if !is_move {
-                        turtle.position += turtle.up() * len;
+    /// Starts a resumable interpretation of `state` as it looked at `time`
+    /// (see [`Self::build_skeleton_at`]).
+    pub fn cursor_at<'a>(&'a self, state: &'a SymbiosState, time: f64) -> InterpretationCursor<'a> {
+        InterpretationCursor::new(self, state, Some(time), self.empty_skeleton())
+    }
 
-                        if let Some(t_vec) = self.config.tropism
-                            && self.config.elasticity > 0.0
-                        {
-                            let head = turtle.up();
-                            let h_cross_t = head.cross(t_vec);
-                            let mag = h_cross_t.length();
-                            if mag > 0.0001 {
-                                let angle = self.config.elasticity * mag;
-                                let axis = h_cross_t.normalize();
-                                turtle.rotate_axis(axis, angle);
-                            }
-                        }
-                    } else {
-                        turtle.position += turtle.up() * len;
-                    }
+    /// An empty skeleton recording the configured heading axis.
+    fn empty_skeleton(&self) -> Skeleton {
+        Skeleton {
+            heading: self.config.conventions.heading,
+            ..Default::default()
+        }
+    }
 
-                    // Push Node with FULL STATE
-                    skeleton.add_node(
-                        SkeletonPoint {
-                            position: turtle.position,
-                            rotation: turtle.rotation,
-                            radius: turtle.width / 2.0,
-                            color: turtle.color,
-                            material_id: turtle.material_id,
-                            uv_scale: turtle.uv_scale,
-                        },
-                        is_move, // Force new strand if this was a Move
-                    );
-                }
-                TurtleOp::Yaw(sign) => {
-                    let angle = get_val(self.config.default_angle.to_degrees()).to_radians() * sign;
-                    turtle.rotate_local_z(angle);
-                }
-                TurtleOp::Pitch(sign) => {
-                    let angle = get_val(self.config.default_angle.to_degrees()).to_radians() * sign;
-                    turtle.rotate_local_x(angle);
-                }
-                TurtleOp::Roll(sign) => {
-                    let angle = get_val(self.config.default_angle.to_degrees()).to_radians() * sign;
-                    turtle.rotate_local_y(angle);
-                }
-                TurtleOp::TurnAround => {
-                    turtle.rotate_local_z(PI);
-                }
-                TurtleOp::Vertical => {
-                    let h = turtle.up();
-                    let v = Vec3::Y;
-                    let l = v.cross(h).normalize_or_zero();
-                    if l.length_squared() > 0.001 {
-                        let u = h.cross(l).normalize();
-                        let rot_matrix = Mat3::from_cols(-l, h, u);
-                        turtle.rotation = Quat::from_mat3(&rot_matrix);
-                    }
-                }
-                TurtleOp::SetWidth => {
-                    turtle.width = get_val(turtle.width);
-                }
-                TurtleOp::SetColor => {
-                    // Logic: Supports 1 arg (Grayscale), 3 args (RGB), 4 args (RGBA)
-                    let count = view.params.len();
-                    match count {
-                        1 => turtle.color = Vec4::new(p0, p0, p0, 1.0),
-                        3 => turtle.color = Vec4::new(p(0, 0.), p(1, 0.), p(2, 0.), 1.0),
-                        4 => turtle.color = Vec4::new(p(0, 0.), p(1, 0.), p(2, 0.), p(3, 1.)),
-                        _ => {} // No change if no params
-                    }
-                }
-                TurtleOp::SetMaterial => {
-                    turtle.material_id = p0 as u8;
-                }
-                TurtleOp::SetUVScale => {
-                    turtle.uv_scale = get_val(1.0).max(0.0);
-                }
-                TurtleOp::Push => {
-                    if stack.len() >= self.config.max_stack_depth {
-                        continue;
-                    }
-                    stack.push(turtle);
-                    // Explicitly break the strand on Push to isolate the branch
-                    skeleton.add_node(
-                        SkeletonPoint {
-                            position: turtle.position,
-                            rotation: turtle.rotation,
-                            radius: turtle.width / 2.0,
-                            color: turtle.color,
-                            material_id: turtle.material_id,
-                            uv_scale: turtle.uv_scale,
-                        },
-                        true,
-                    );
-                }
-                TurtleOp::Pop => {
-                    if let Some(saved_state) = stack.pop() {
-                        turtle = saved_state;
-                        skeleton.add_node(
-                            SkeletonPoint {
-                                position: turtle.position,
-                                rotation: turtle.rotation,
-                                radius: turtle.width / 2.0,
-                                color: turtle.color,
-                                material_id: turtle.material_id,
-                                uv_scale: turtle.uv_scale,
-                            },
-                            true,
-                        );
-                    }
-                }
-                TurtleOp::Spawn(default_id) => {
-                    let prop_id = view
-                        .params
-                        .first()
-                        .map(|&x| x as u16)
-                        .unwrap_or(*default_id);
-                    let scale_scalar = view.params.get(1).map(|&x| x as f32).unwrap_or(1.0);
+    /// Interprets `state` into a custom [`TurtleSink`] instead of a [`Skeleton`],
+    /// returning the patches captured by [`TurtleOp::Query`] modules.
+    ///
+    /// Skeleton post-processes such as [`TurtleConfig::rotation_minimizing_frames`]
+    /// are not applied.
+    pub fn build_into<S: TurtleSink + ?Sized>(
+        &self,
+        state: &SymbiosState,
+        sink: &mut S,
+    ) -> Vec<QueryPatch> {
+        self.cursor_into(state, sink).finish_into().1
+    }
 
-                    skeleton.add_prop(crate::skeleton::SkeletonProp {
-                        prop_id,
-                        position: turtle.position,
-                        rotation: turtle.rotation,
-                        scale: Vec3::splat(scale_scalar),
-                        color: turtle.color,
-                        material_id: turtle.material_id,
-                    });
-                }
-                TurtleOp::Ignore => {}
-            }
-        }
-        skeleton
+    /// Starts a resumable interpretation of `state` into a custom [`TurtleSink`].
+    pub fn cursor_into<'a, S: TurtleSink>(
+        &'a self,
+        state: &'a SymbiosState,
+        sink: S,
+    ) -> InterpretationCursor<'a, S> {
+        InterpretationCursor::new(self, state, None, sink)
     }
 }
@@ -0,0 +1,87 @@
+//! Deterministic, branch-stable stochastic jitter for turtle operations.
+//!
+//! Random values are derived by hashing the branch path and the symbol's index
+//! within its branch, rather than drawing from a sequential RNG. Editing one
+//! branch of a grammar therefore leaves the jitter of every other branch intact.
+
+use serde::{Deserialize, Serialize};
+
+/// Jitter amplitudes applied by [`TurtleInterpreter::build_skeleton`](crate::TurtleInterpreter::build_skeleton).
+///
+/// Each sample is uniformly distributed in `[-amplitude, amplitude]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JitterConfig {
+    /// Maximum deviation (in radians) added to Yaw/Pitch/Roll angles.
+    pub angle: f32,
+    /// Maximum relative deviation of Draw/Move step lengths (0.1 = ±10%).
+    pub step: f32,
+    /// Maximum relative deviation of widths set by SetWidth (0.1 = ±10%).
+    pub width: f32,
+    /// Seed mixed into every hash; change it to get a different variation.
+    pub seed: u64,
+}
+
+/// Independent random channels so one symbol can jitter several quantities.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum JitterChannel {
+    Angle = 1,
+    Step = 2,
+    Width = 3,
+}
+
+/// Position of the interpreter within the branch tree.
+///
+/// Saved and restored alongside the turtle on Push/Pop.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct BranchPath {
+    hash: u64,
+    index: u64,
+}
+
+impl BranchPath {
+    /// Returns the path of the trunk for the given seed.
+    pub(crate) fn root(seed: u64) -> Self {
+        Self {
+            hash: mix(seed),
+            index: 0,
+        }
+    }
+
+    /// Returns the key of the next symbol in this branch and advances the index.
+    pub(crate) fn advance(&mut self) -> SymbolKey {
+        let key = SymbolKey(mix(self.hash ^ mix(self.index)));
+        self.index += 1;
+        key
+    }
+
+    /// Returns the path of a branch opened by the symbol with the given key.
+    pub(crate) fn branch(key: SymbolKey) -> Self {
+        Self {
+            hash: key.0,
+            index: 0,
+        }
+    }
+}
+
+/// Stable hash identifying a single symbol by branch path and index.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SymbolKey(u64);
+
+impl SymbolKey {
+    /// Returns a uniform sample in `[-1, 1]` for the given channel.
+    pub(crate) fn sample(self, channel: JitterChannel) -> f32 {
+        let bits = mix(self.0 ^ (channel as u64).wrapping_mul(0xA076_1D64_78BD_642F));
+        // Top 24 bits give an exactly representable f32 in [0, 1)
+        let unit = (bits >> 40) as f32 / (1u64 << 24) as f32;
+        unit * 2.0 - 1.0
+    }
+}
+
+/// SplitMix64 finalizer.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
@@ -10,8 +10,32 @@
 //!
 //! - Standard L-System turtle operations (draw, move, rotate, branch)
 //! - Palette-based material system with per-segment color, material ID, and UV scale
-//! - Tropism support for natural plant-like growth
+//! - Tropism support for natural plant-like growth, with per-branch overrides and
+//!   directional, point and vector-field terms
+//! - Seeded, branch-stable jitter of angles, step lengths and widths
+//! - Age-driven growth animation from module birth times
+//! - Resumable, chunked interpretation for spreading work across frames
+//! - User-defined custom operations with handler callbacks
+//! - Diagnostics report for malformed strings, with an optional strict mode
+//! - Resource limits, cancellation and progress reporting for untrusted grammars
+//! - Serializable interpretation profiles with standard, ABOP/cpfg and Houdini presets
 //! - Prop spawning for discrete objects (leaves, flowers)
+//! - Open L-system query modules (`?P`, `?H`, `?U`) that write turtle state back
+//! - Branch topology (parent strand, attachment point, depth) with traversal helpers
+//! - ABOP-style polygon surfaces (`{`, `.`, `}`) with ear-clipping triangulation
+//! - Pipe-model (da Vinci) automatic branch radii
+//! - Catmull-Rom and B-spline strand smoothing
+//! - Rotation-minimizing frames for twist-free strand orientation
+//! - Signed-distance obstacle environments with stop, deflect and terminate policies
+//! - Bounding-volume hierarchy for ray picking and proximity queries
+//! - Streaming output through the [`TurtleSink`] trait, with [`Skeleton`] as the default sink
+//! - Configurable heading axis, world up (Y-up or Z-up) and rotation handedness
+//! - Renderer-agnostic tube mesh generation with per-material submeshes
+//! - Wavefront OBJ/MTL export of polylines or tube meshes
+//! - Binary glTF export with GPU-instanced props (`EXT_mesh_gpu_instancing`)
+//! - Bone hierarchies generated from strand topology, exported as glTF skins
+//! - SVG export through orthographic plane or camera projections
+//! - SWC morphology import and export for neuron and vasculature tools
 //!
 //! ## Example
 //!
@@ -31,10 +55,53 @@
 //! let skeleton = interpreter.build_skeleton(&state);
 //! ```
 
+pub mod bones;
+pub mod conventions;
+pub mod cursor;
+pub mod diagnostics;
+pub mod environment;
+mod frames;
+pub mod gltf;
+pub mod growth;
 pub mod interpreter;
+pub mod jitter;
+pub mod limits;
+pub mod mesh;
+pub mod obj;
+pub mod pipe_model;
+pub mod profile;
+pub mod query;
+pub mod sink;
 pub mod skeleton;
+pub mod smoothing;
+pub mod spatial;
+pub mod svg;
+pub mod swc;
+pub mod tropism;
 pub mod turtle;
 
-pub use interpreter::{TurtleConfig, TurtleInterpreter};
-pub use skeleton::{Skeleton, SkeletonPoint, SkeletonProp};
+pub use bones::{BoneHierarchy, Joint};
+pub use conventions::{Axis, Conventions, Handedness};
+pub use cursor::InterpretationCursor;
+pub use diagnostics::{
+    Diagnostic, DiagnosticKind, InterpretError, InterpretationReport, UnmappedSymbol,
+};
+pub use environment::{Environment, ObstaclePolicy, TerminatedBranch};
+pub use gltf::{GltfConfig, save_glb, write_glb};
+pub use growth::{GrowthConfig, GrowthCurve};
+pub use interpreter::{AngleUnit, CustomOpHandler, TurtleConfig, TurtleInterpreter};
+pub use jitter::JitterConfig;
+pub use limits::{CancellationToken, Limit, ProgressCallback, ResourceLimits};
+pub use mesh::{MeshConfig, SkeletonMesh, SubMesh};
+pub use obj::{ObjConfig, ObjGeometry, save_obj, write_obj};
+pub use pipe_model::PipeModelConfig;
+pub use profile::InterpretationProfile;
+pub use query::{QueryKind, QueryPatch, apply_query_patches};
+pub use sink::TurtleSink;
+pub use skeleton::{Skeleton, SkeletonPoint, SkeletonPolygon, SkeletonProp, StrandInfo};
+pub use smoothing::{SmoothingConfig, SplineKind};
+pub use spatial::{Aabb, BoundingSphere, NearestHit, RayHit, SkeletonBvh, SpatialElement};
+pub use svg::{Projection, SvgConfig, save_svg, write_svg};
+pub use swc::{load_swc, read_swc, save_swc, write_swc};
+pub use tropism::{Tropism, TropismField};
 pub use turtle::{TurtleOp, TurtleState};
@@ -0,0 +1,66 @@
+//! Resource limits and cancellation.
+//!
+//! [`TurtleConfig::max_stack_depth`](crate::TurtleConfig::max_stack_depth) bounds
+//! recursion, but a flat string can still emit unbounded geometry. [`ResourceLimits`]
+//! cap the output of one interpretation, and a [`CancellationToken`] lets another
+//! thread stop it. Either way interpretation stops cleanly: the output built so far is
+//! kept and the reason is reported as a
+//! [`DiagnosticKind`](crate::DiagnosticKind).
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Caps on the output of a single interpretation. `None` means unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceLimits {
+    /// Maximum number of strands begun.
+    pub max_strands: Option<usize>,
+    /// Maximum number of points emitted, strand starts included.
+    pub max_points: Option<usize>,
+    /// Maximum number of props spawned.
+    pub max_props: Option<usize>,
+    /// Maximum total length of Draw steps. Move steps are free.
+    pub max_drawn_length: Option<f32>,
+}
+
+/// A limit from [`ResourceLimits`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Limit {
+    Strands,
+    Points,
+    Props,
+    DrawnLength,
+}
+
+/// Shared flag that stops interpretations started by an interpreter it is attached
+/// to with [`TurtleInterpreter::with_cancellation`](crate::TurtleInterpreter::with_cancellation).
+///
+/// Clones share the flag, so one clone can be handed to another thread (e.g. a
+/// request timeout) while the interpreter holds the other.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation. Running interpretations stop at their next poll.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` once [`Self::cancel`] has been called on any clone.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Callback registered with
+/// [`TurtleInterpreter::with_progress`](crate::TurtleInterpreter::with_progress).
+///
+/// Receives the fraction of the string interpreted so far, in `[0, 1]`.
+pub type ProgressCallback = Arc<dyn Fn(f32) + Send + Sync>;
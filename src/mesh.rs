@@ -0,0 +1,261 @@
+//! Renderer-agnostic generalized-cylinder mesh generation from a [`Skeleton`].
+//!
+//! Each strand is swept with a ring of vertices whose radius, color, material ID
+//! and UV scale come from the corresponding [`SkeletonPoint`]. Triangles are
+//! grouped into [`SubMesh`]es by `material_id` so that callers can bind one
+//! palette material per draw call.
+
+use crate::conventions::Axis;
+use crate::skeleton::{Skeleton, SkeletonPoint, strand_tangent};
+use glam::{Vec2, Vec3, Vec4};
+use std::f32::consts::TAU;
+
+/// Configuration for tube mesh generation.
+#[derive(Clone, Copy, Debug)]
+pub struct MeshConfig {
+    /// Number of vertices around each ring (clamped to a minimum of 3).
+    pub resolution: u32,
+    /// Close the start and end of every strand with a triangle fan.
+    pub end_caps: bool,
+}
+
+impl Default for MeshConfig {
+    fn default() -> Self {
+        Self {
+            resolution: 8,
+            end_caps: true,
+        }
+    }
+}
+
+/// A range of triangle indices sharing one material palette entry.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SubMesh {
+    /// Material palette ID shared by every triangle in this submesh.
+    pub material_id: u8,
+    /// Triangle list indices into the parent [`SkeletonMesh`] vertex arrays.
+    pub indices: Vec<u32>,
+}
+
+/// An indexed triangle mesh generated from a [`Skeleton`].
+///
+/// Vertex attributes are stored as parallel arrays; triangles are split into
+/// [`SubMesh`]es by material ID, sorted by ascending ID.
+#[derive(Clone, Debug, Default)]
+pub struct SkeletonMesh {
+    /// Vertex positions.
+    pub positions: Vec<Vec3>,
+    /// Unit vertex normals.
+    pub normals: Vec<Vec3>,
+    /// Texture coordinates. `u` wraps around the ring, `v` runs along the strand.
+    pub uvs: Vec<Vec2>,
+    /// RGBA vertex colors.
+    pub colors: Vec<Vec4>,
+    /// Material palette ID per vertex.
+    pub material_ids: Vec<u8>,
+    /// Triangle lists grouped by material ID.
+    pub submeshes: Vec<SubMesh>,
+}
+
+impl SkeletonMesh {
+    /// Sweeps a ring along every strand of `skeleton` and returns the resulting mesh.
+    ///
+    /// Strands with fewer than two points produce no geometry.
+    pub fn build(skeleton: &Skeleton, config: &MeshConfig) -> Self {
+        Self::build_inner(skeleton, config, None)
+    }
+
+    /// Like [`Self::build`], also returning the `(strand, point)` every vertex was
+    /// generated from.
+    pub(crate) fn build_with_sources(
+        skeleton: &Skeleton,
+        config: &MeshConfig,
+    ) -> (Self, Vec<(usize, usize)>) {
+        let mut sources = Vec::new();
+        let mesh = Self::build_inner(skeleton, config, Some(&mut sources));
+        (mesh, sources)
+    }
+
+    fn build_inner(
+        skeleton: &Skeleton,
+        config: &MeshConfig,
+        mut sources: Option<&mut Vec<(usize, usize)>>,
+    ) -> Self {
+        let mut mesh = Self::default();
+        let resolution = config.resolution.max(3);
+
+        for (index, strand) in skeleton.strands.iter().enumerate() {
+            if strand.len() < 2 {
+                continue;
+            }
+            let first_vertex = mesh.positions.len();
+            mesh.add_strand(strand, skeleton.heading, resolution, config.end_caps);
+
+            if let Some(sources) = sources.as_deref_mut() {
+                // Rings in point order, then the start cap, then the end cap
+                let ring_len = resolution as usize + 1;
+                let rings_end = strand.len() * ring_len;
+                let start_cap_end = rings_end + ring_len;
+                let last = strand.len() - 1;
+                sources.extend((0..mesh.positions.len() - first_vertex).map(|v| {
+                    let point = match v {
+                        v if v < rings_end => v / ring_len,
+                        v if v < start_cap_end => 0,
+                        _ => last,
+                    };
+                    (index, point)
+                }));
+            }
+        }
+
+        mesh.submeshes.sort_by_key(|s| s.material_id);
+        mesh
+    }
+
+    /// Returns the number of vertices in the mesh.
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns the total number of triangles across all submeshes.
+    pub fn triangle_count(&self) -> usize {
+        self.submeshes.iter().map(|s| s.indices.len() / 3).sum()
+    }
+
+    /// Returns a single triangle list covering every submesh.
+    pub fn indices(&self) -> Vec<u32> {
+        self.submeshes
+            .iter()
+            .flat_map(|s| s.indices.iter().copied())
+            .collect()
+    }
+
+    fn submesh_mut(&mut self, material_id: u8) -> &mut Vec<u32> {
+        let idx = match self
+            .submeshes
+            .iter()
+            .position(|s| s.material_id == material_id)
+        {
+            Some(idx) => idx,
+            None => {
+                self.submeshes.push(SubMesh {
+                    material_id,
+                    indices: Vec::new(),
+                });
+                self.submeshes.len() - 1
+            }
+        };
+        &mut self.submeshes[idx].indices
+    }
+
+    fn push_vertex(&mut self, position: Vec3, normal: Vec3, uv: Vec2, point: &SkeletonPoint) {
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.uvs.push(uv);
+        self.colors.push(point.color);
+        self.material_ids.push(point.material_id);
+    }
+
+    fn add_strand(
+        &mut self,
+        strand: &[SkeletonPoint],
+        heading: Axis,
+        resolution: u32,
+        end_caps: bool,
+    ) {
+        let ring_len = resolution + 1; // Duplicate seam vertex for UV continuity
+        let first_ring = self.positions.len() as u32;
+        let mut v = 0.0;
+        let mut tangents = Vec::with_capacity(strand.len());
+
+        for (i, point) in strand.iter().enumerate() {
+            if i > 0 {
+                let seg_len = point.position.distance(strand[i - 1].position);
+                v += seg_len * point.uv_scale;
+            }
+
+            let tangent = strand_tangent(strand, i, heading);
+            let (side, binormal) = ring_basis(point, heading, tangent);
+            tangents.push(tangent);
+
+            for j in 0..ring_len {
+                let u = j as f32 / resolution as f32;
+                let (sin, cos) = (u * TAU).sin_cos();
+                let normal = (side * cos + binormal * sin).normalize_or_zero();
+                let position = point.position + normal * point.radius;
+                self.push_vertex(position, normal, Vec2::new(u, v), point);
+            }
+        }
+
+        for i in 1..strand.len() as u32 {
+            let a = first_ring + (i - 1) * ring_len;
+            let b = first_ring + i * ring_len;
+            let material_id = strand[i as usize].material_id;
+            let indices = self.submesh_mut(material_id);
+            for j in 0..resolution {
+                indices.extend_from_slice(&[a + j, a + j + 1, b + j]);
+                indices.extend_from_slice(&[a + j + 1, b + j + 1, b + j]);
+            }
+        }
+
+        if end_caps {
+            let last = strand.len() - 1;
+            let start_ring = first_ring;
+            let end_ring = first_ring + last as u32 * ring_len;
+            self.add_cap(&strand[0], -tangents[0], start_ring, resolution, true);
+            self.add_cap(&strand[last], tangents[last], end_ring, resolution, false);
+        }
+    }
+
+    fn add_cap(
+        &mut self,
+        point: &SkeletonPoint,
+        normal: Vec3,
+        ring_start: u32,
+        resolution: u32,
+        flip: bool,
+    ) {
+        let center = self.positions.len() as u32;
+        self.push_vertex(point.position, normal, Vec2::splat(0.5), point);
+
+        // Rim vertices are duplicated so the cap shades flat, with planar UVs around the centre.
+        let rim_start = self.positions.len() as u32;
+        for j in 0..resolution {
+            let position = self.positions[(ring_start + j) as usize];
+            let (sin, cos) = (j as f32 / resolution as f32 * TAU).sin_cos();
+            let uv = Vec2::new(0.5 + 0.5 * cos, 0.5 + 0.5 * sin);
+            self.push_vertex(position, normal, uv, point);
+        }
+
+        let indices = self.submesh_mut(point.material_id);
+        for j in 0..resolution {
+            let a = rim_start + j;
+            let b = rim_start + (j + 1) % resolution;
+            if flip {
+                indices.extend_from_slice(&[center, b, a]);
+            } else {
+                indices.extend_from_slice(&[center, a, b]);
+            }
+        }
+    }
+}
+
+/// Builds an orthonormal ring basis perpendicular to `tangent`, oriented by the point's rotation.
+///
+/// The rotation is first re-expressed with its `heading` axis as local +Y, so the ring
+/// starts at the same side of the strand under every [`Conventions`](crate::Conventions).
+fn ring_basis(point: &SkeletonPoint, heading: Axis, tangent: Vec3) -> (Vec3, Vec3) {
+    let rotation = point.rotation * heading.rotation_from_y();
+    let mut reference = rotation * Vec3::X;
+    if reference.dot(tangent).abs() > 0.99 {
+        reference = rotation * Vec3::Z;
+    }
+    let side = (reference - tangent * reference.dot(tangent)).normalize_or_zero();
+    let side = if side == Vec3::ZERO {
+        tangent.any_orthonormal_vector()
+    } else {
+        side
+    };
+    let binormal = tangent.cross(side);
+    (side, binormal)
+}
@@ -0,0 +1,253 @@
+//! Wavefront OBJ/MTL export.
+//!
+//! Writes a [`Skeleton`] as an `.obj` file with a companion `.mtl` file, so trees can
+//! be handed to tools that only read OBJ. Strands are written either as raw polylines
+//! (`l` elements) or as the tube mesh from [`SkeletonMesh::build`]; polygons become
+//! faces and props become named placeholder groups. Geometry is split into one
+//! `usemtl` group per `material_id`.
+
+use crate::mesh::{MeshConfig, SkeletonMesh};
+use crate::skeleton::Skeleton;
+use glam::{Vec3, Vec4};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// How strands are written.
+#[derive(Clone, Copy, Debug)]
+pub enum ObjGeometry {
+    /// One `l` element per run of segments sharing a material.
+    Polylines,
+    /// The triangulated tube mesh, with normals and texture coordinates.
+    Tubes(MeshConfig),
+}
+
+impl Default for ObjGeometry {
+    fn default() -> Self {
+        ObjGeometry::Tubes(MeshConfig::default())
+    }
+}
+
+/// Configuration for OBJ export.
+#[derive(Clone, Copy, Debug)]
+pub struct ObjConfig {
+    /// How strands are written.
+    pub geometry: ObjGeometry,
+    /// Write polygons as triangulated faces in a `polygons` group.
+    pub polygons: bool,
+    /// Write every prop as a group named `prop_<prop_id>_<index>`.
+    ///
+    /// OBJ has no instancing, so each group holds a placeholder line from the prop's
+    /// position along its local heading axis ([`Skeleton::heading`]), scaled by the
+    /// matching scale component, to be replaced by the asset.
+    pub props: bool,
+}
+
+impl Default for ObjConfig {
+    fn default() -> Self {
+        Self {
+            geometry: ObjGeometry::default(),
+            polygons: true,
+            props: true,
+        }
+    }
+}
+
+/// Writes `skeleton` as OBJ to `obj` and its materials as MTL to `mtl`.
+///
+/// `mtl_name` is the file name the OBJ's `mtllib` statement refers to. Materials are
+/// named `material_<id>`; since palettes are defined externally, each gets the
+/// average color of the geometry using it as its diffuse color.
+pub fn write_obj(
+    skeleton: &Skeleton,
+    config: &ObjConfig,
+    mtl_name: &str,
+    obj: &mut impl Write,
+    mtl: &mut impl Write,
+) -> io::Result<()> {
+    let mut writer = ObjWriter {
+        out: obj,
+        vertex_count: 0,
+        colors: BTreeMap::new(),
+    };
+    writeln!(writer.out, "# symbios-turtle-3d")?;
+    writeln!(writer.out, "mtllib {mtl_name}")?;
+
+    match config.geometry {
+        ObjGeometry::Polylines => writer.write_polylines(skeleton)?,
+        ObjGeometry::Tubes(mesh_config) => {
+            writer.write_mesh(&SkeletonMesh::build(skeleton, &mesh_config))?
+        }
+    }
+    if config.polygons {
+        writer.write_polygons(skeleton)?;
+    }
+    if config.props {
+        writer.write_props(skeleton)?;
+    }
+
+    for (material_id, (sum, count)) in &writer.colors {
+        let color = *sum / *count as f32;
+        writeln!(mtl, "newmtl {}", material_name(*material_id))?;
+        writeln!(mtl, "Kd {} {} {}", color.x, color.y, color.z)?;
+        if color.w < 1.0 {
+            writeln!(mtl, "d {}", color.w)?;
+        }
+        writeln!(mtl)?;
+    }
+    Ok(())
+}
+
+/// Writes `skeleton` to the OBJ file at `path` and its materials to a `.mtl` file with
+/// the same stem next to it.
+pub fn save_obj(skeleton: &Skeleton, config: &ObjConfig, path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    let mtl_path = path.with_extension("mtl");
+    let mtl_name = mtl_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut obj = BufWriter::new(File::create(path)?);
+    let mut mtl = BufWriter::new(File::create(&mtl_path)?);
+    write_obj(skeleton, config, &mtl_name, &mut obj, &mut mtl)?;
+    obj.flush()?;
+    mtl.flush()
+}
+
+fn material_name(material_id: u8) -> String {
+    format!("material_{material_id}")
+}
+
+/// OBJ output state: the running vertex count for 1-based indices and the color
+/// accumulated per material for the MTL.
+struct ObjWriter<'w, W: Write> {
+    out: &'w mut W,
+    vertex_count: u32,
+    colors: BTreeMap<u8, (Vec4, usize)>,
+}
+
+impl<W: Write> ObjWriter<'_, W> {
+    /// Writes a vertex and returns its 1-based index.
+    fn vertex(&mut self, position: Vec3) -> io::Result<u32> {
+        writeln!(self.out, "v {} {} {}", position.x, position.y, position.z)?;
+        self.vertex_count += 1;
+        Ok(self.vertex_count)
+    }
+
+    fn use_color(&mut self, material_id: u8, color: Vec4) {
+        let entry = self.colors.entry(material_id).or_insert((Vec4::ZERO, 0));
+        entry.0 += color;
+        entry.1 += 1;
+    }
+
+    fn use_material(&mut self, material_id: u8) -> io::Result<()> {
+        writeln!(self.out, "usemtl {}", material_name(material_id))
+    }
+
+    fn write_polylines(&mut self, skeleton: &Skeleton) -> io::Result<()> {
+        writeln!(self.out, "g strands")?;
+        // Runs of consecutive segments sharing a material, keyed by that material
+        let mut runs: BTreeMap<u8, Vec<Vec<u32>>> = BTreeMap::new();
+        for strand in &skeleton.strands {
+            let base = self.vertex_count;
+            for point in strand {
+                self.vertex(point.position)?;
+            }
+            // A segment takes the material of its end point, as in the tube mesh
+            let mut previous: Option<u8> = None;
+            for (i, point) in strand.iter().enumerate().skip(1) {
+                self.use_color(point.material_id, point.color);
+                let run = runs.entry(point.material_id).or_default();
+                if previous != Some(point.material_id) {
+                    run.push(vec![base + i as u32]);
+                }
+                if let Some(run) = run.last_mut() {
+                    run.push(base + i as u32 + 1);
+                }
+                previous = Some(point.material_id);
+            }
+        }
+        for (material_id, runs) in runs {
+            self.use_material(material_id)?;
+            for run in runs {
+                let indices: Vec<String> = run.iter().map(u32::to_string).collect();
+                writeln!(self.out, "l {}", indices.join(" "))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_mesh(&mut self, mesh: &SkeletonMesh) -> io::Result<()> {
+        writeln!(self.out, "g strands")?;
+        let base = self.vertex_count;
+        for position in &mesh.positions {
+            self.vertex(*position)?;
+        }
+        for uv in &mesh.uvs {
+            writeln!(self.out, "vt {} {}", uv.x, uv.y)?;
+        }
+        for normal in &mesh.normals {
+            writeln!(self.out, "vn {} {} {}", normal.x, normal.y, normal.z)?;
+        }
+        for (material_id, color) in mesh.material_ids.iter().zip(&mesh.colors) {
+            self.use_color(*material_id, *color);
+        }
+        // Texture coordinates and normals are only written for the mesh, so their
+        // indices need no offset
+        for submesh in &mesh.submeshes {
+            self.use_material(submesh.material_id)?;
+            for triangle in submesh.indices.chunks_exact(3) {
+                let corner = |i: u32| format!("{}/{}/{}", base + i + 1, i + 1, i + 1);
+                writeln!(
+                    self.out,
+                    "f {} {} {}",
+                    corner(triangle[0]),
+                    corner(triangle[1]),
+                    corner(triangle[2])
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_polygons(&mut self, skeleton: &Skeleton) -> io::Result<()> {
+        if skeleton.polygons.is_empty() {
+            return Ok(());
+        }
+        writeln!(self.out, "g polygons")?;
+        for polygon in &skeleton.polygons {
+            let base = self.vertex_count;
+            for vertex in &polygon.vertices {
+                self.vertex(*vertex)?;
+            }
+            self.use_color(polygon.material_id, polygon.color);
+            self.use_material(polygon.material_id)?;
+            for [a, b, c] in polygon.triangulate() {
+                writeln!(
+                    self.out,
+                    "f {} {} {}",
+                    base + a + 1,
+                    base + b + 1,
+                    base + c + 1
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_props(&mut self, skeleton: &Skeleton) -> io::Result<()> {
+        for (index, prop) in skeleton.props.iter().enumerate() {
+            writeln!(self.out, "g prop_{}_{}", prop.prop_id, index)?;
+            let axis = skeleton.heading.unit();
+            let heading = prop.rotation * axis * axis.dot(prop.scale);
+            let start = self.vertex(prop.position)?;
+            let end = self.vertex(prop.position + heading)?;
+            self.use_color(prop.material_id, prop.color);
+            self.use_material(prop.material_id)?;
+            writeln!(self.out, "l {start} {end}")?;
+        }
+        Ok(())
+    }
+}
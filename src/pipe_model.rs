@@ -0,0 +1,85 @@
+//! Pipe-model (da Vinci) radius computation.
+//!
+//! Treats every branch tip as a bundle of pipes and propagates radii from the tips
+//! toward the roots so that `parent^n = Σ child^n` at every junction. This gives
+//! physically plausible tapering without threading width parameters through the grammar.
+
+use crate::skeleton::Skeleton;
+
+/// Configuration for [`Skeleton::apply_pipe_model`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PipeModelConfig {
+    /// Exponent `n` in `parent^n = Σ child^n`. `2.0` preserves cross-sectional area
+    /// (da Vinci's rule); measured trees typically fall between `2.0` and `3.0`.
+    pub exponent: f32,
+    /// Radius assigned to every free branch tip.
+    pub tip_radius: f32,
+    /// Never exceed the radius specified by `!` in the grammar.
+    pub clamp_to_specified: bool,
+}
+
+impl Default for PipeModelConfig {
+    fn default() -> Self {
+        Self {
+            exponent: 2.0,
+            tip_radius: 0.01,
+            clamp_to_specified: false,
+        }
+    }
+}
+
+impl Skeleton {
+    /// Recomputes every [`SkeletonPoint::radius`](crate::SkeletonPoint::radius) bottom-up
+    /// from the branch tips using the pipe model.
+    ///
+    /// Relies on [`Skeleton::strand_info`] to find where branches join; strands without
+    /// topology and [detached](crate::StrandInfo::detached) strands started by a Move are
+    /// treated as independent roots. Single-point strands with no children carry no flow.
+    pub fn apply_pipe_model(&mut self, config: &PipeModelConfig) {
+        let n = config.exponent.max(f32::EPSILON);
+        let tip_flow = config.tip_radius.max(0.0).powf(n);
+        let strand_count = self.strands.len();
+
+        // Children must be resolved before their parents: reverse pre-order does that.
+        let mut order = Vec::with_capacity(strand_count);
+        for root in self.roots().collect::<Vec<_>>() {
+            order.extend(self.subtree(root));
+        }
+
+        // Flow contributed by children at each (parent, point) junction.
+        let mut junction_flow: Vec<Vec<f32>> =
+            self.strands.iter().map(|s| vec![0.0; s.len()]).collect();
+
+        for &s in order.iter().rev() {
+            let len = self.strands[s].len();
+            if len == 0 {
+                continue;
+            }
+
+            let mut acc = if len >= 2 && junction_flow[s][len - 1] == 0.0 {
+                tip_flow
+            } else {
+                0.0
+            };
+            for k in (0..len).rev() {
+                acc += junction_flow[s][k];
+                let point = &mut self.strands[s][k];
+                let mut radius = acc.powf(1.0 / n);
+                if config.clamp_to_specified {
+                    radius = radius.min(point.radius);
+                }
+                point.radius = radius;
+            }
+
+            let base_flow = self.strands[s][0].radius.powf(n);
+            let parent_point = self.strand_info(s).parent_point;
+            if let Some(parent) = self.parent(s)
+                && let Some(flow) = junction_flow
+                    .get_mut(parent)
+                    .and_then(|points| points.get_mut(parent_point))
+            {
+                *flow += base_flow;
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Serializable interpretation profiles.
+//!
+//! An [`InterpretationProfile`] bundles a [`TurtleConfig`] with a symbol-name-to-op
+//! map, so conventions can be stored as JSON, swapped per project and bound to any
+//! [`SymbolTable`] without hand-written [`TurtleInterpreter::set_op`] calls.
+
+use crate::interpreter::{STANDARD_SYMBOLS, TurtleConfig, TurtleInterpreter};
+use crate::query::QueryKind;
+use crate::turtle::TurtleOp;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use symbios::SymbolTable;
+
+/// A named [`TurtleConfig`] plus the ops its symbols map to.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InterpretationProfile {
+    /// Human-readable name of the convention.
+    pub name: String,
+    /// Interpretation settings; missing fields take their default values.
+    #[serde(default)]
+    pub config: TurtleConfig,
+    /// Ops keyed by symbol name.
+    #[serde(default)]
+    pub symbols: BTreeMap<String, TurtleOp>,
+}
+
+impl InterpretationProfile {
+    /// Creates an empty profile with the default config.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            config: TurtleConfig::default(),
+            symbols: BTreeMap::new(),
+        }
+    }
+
+    /// Builder method to map `symbol` to `op`, replacing any previous mapping.
+    pub fn with_symbol(mut self, symbol: impl Into<String>, op: TurtleOp) -> Self {
+        self.symbols.insert(symbol.into(), op);
+        self
+    }
+
+    /// The mapping installed by [`TurtleInterpreter::populate_standard_symbols`].
+    pub fn standard() -> Self {
+        let mut profile = Self::new("standard");
+        for &(sym, op) in STANDARD_SYMBOLS {
+            profile.symbols.insert(sym.to_string(), op);
+        }
+        profile
+    }
+
+    /// The conventions of *The Algorithmic Beauty of Plants* and cpfg.
+    ///
+    /// Like [`Self::standard`], but `~` incorporates predefined surface 0 and the
+    /// material extensions (`'`, `,`, `;`) are left unmapped, since cpfg uses those
+    /// symbols for color-index arithmetic. Angle parameters are in degrees.
+    pub fn abop() -> Self {
+        let mut profile = Self::new("abop");
+        let symbols = [
+            ("F", TurtleOp::Draw),
+            ("f", TurtleOp::Move),
+            ("+", TurtleOp::Yaw(1.0)),
+            ("-", TurtleOp::Yaw(-1.0)),
+            ("&", TurtleOp::Pitch(1.0)),
+            ("^", TurtleOp::Pitch(-1.0)),
+            ("\\", TurtleOp::Roll(1.0)),
+            ("/", TurtleOp::Roll(-1.0)),
+            ("|", TurtleOp::TurnAround),
+            ("$", TurtleOp::Vertical),
+            ("!", TurtleOp::SetWidth),
+            ("[", TurtleOp::Push),
+            ("]", TurtleOp::Pop),
+            ("%", TurtleOp::Cut),
+            ("~", TurtleOp::Spawn(0)),
+            ("{", TurtleOp::BeginPolygon),
+            ("}", TurtleOp::EndPolygon),
+            (".", TurtleOp::RecordVertex),
+            ("?P", TurtleOp::Query(QueryKind::Position)),
+            ("?H", TurtleOp::Query(QueryKind::Heading)),
+            ("?U", TurtleOp::Query(QueryKind::Up)),
+        ];
+        for (sym, op) in symbols {
+            profile.symbols.insert(sym.to_string(), op);
+        }
+        profile
+    }
+
+    /// The conventions of Houdini's L-System SOP.
+    ///
+    /// Houdini's `+`/`-` turn right/left and `&`/`^` pitch up/down, the opposite
+    /// sense to ABOP. `G` moves without drawing, and `J`, `K` and `M` copy the SOP's
+    /// leaf inputs, mapped here to prop IDs 0, 1 and 2. Angle parameters are in
+    /// degrees, as in Houdini.
+    pub fn houdini() -> Self {
+        let mut profile = Self::new("houdini");
+        let symbols = [
+            ("F", TurtleOp::Draw),
+            ("f", TurtleOp::Move),
+            ("G", TurtleOp::Move),
+            ("+", TurtleOp::Yaw(-1.0)),
+            ("-", TurtleOp::Yaw(1.0)),
+            ("&", TurtleOp::Pitch(-1.0)),
+            ("^", TurtleOp::Pitch(1.0)),
+            ("\\", TurtleOp::Roll(1.0)),
+            ("/", TurtleOp::Roll(-1.0)),
+            ("|", TurtleOp::TurnAround),
+            ("$", TurtleOp::Vertical),
+            ("!", TurtleOp::SetWidth),
+            ("[", TurtleOp::Push),
+            ("]", TurtleOp::Pop),
+            ("%", TurtleOp::Cut),
+            ("{", TurtleOp::BeginPolygon),
+            ("}", TurtleOp::EndPolygon),
+            (".", TurtleOp::RecordVertex),
+            ("J", TurtleOp::Spawn(0)),
+            ("K", TurtleOp::Spawn(1)),
+            ("M", TurtleOp::Spawn(2)),
+        ];
+        for (sym, op) in symbols {
+            profile.symbols.insert(sym.to_string(), op);
+        }
+        profile
+    }
+
+    /// Parses a profile from JSON.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Serializes the profile as pretty-printed JSON.
+    ///
+    /// Fails if the config holds closures ([`Tropism::Field`](crate::Tropism::Field),
+    /// [`GrowthCurve::Custom`](crate::GrowthCurve::Custom)).
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Creates an interpreter with this profile's config and maps every symbol that
+    /// `interner` knows. Symbols missing from the table are skipped.
+    pub fn bind(&self, interner: &SymbolTable) -> TurtleInterpreter {
+        let mut interpreter = TurtleInterpreter::new(self.config.clone());
+        self.apply_symbols(&mut interpreter, interner);
+        interpreter
+    }
+
+    /// Maps every symbol of this profile that `interner` knows onto `interpreter`,
+    /// keeping its config and other mappings.
+    pub fn apply_symbols(&self, interpreter: &mut TurtleInterpreter, interner: &SymbolTable) {
+        for (sym, op) in &self.symbols {
+            if let Some(id) = interner.resolve_id(sym) {
+                interpreter.set_op(id, *op);
+            }
+        }
+    }
+}
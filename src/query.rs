@@ -0,0 +1,69 @@
+//! Environmentally-sensitive (open) L-system query modules.
+//!
+//! A [`TurtleOp::Query`](crate::TurtleOp::Query) module records the turtle's position
+//! or orientation at the point where it is interpreted, mirroring the `?P`/`?H`/`?U`
+//! modules of cpfg. The recorded values are returned as [`QueryPatch`]es which can be
+//! written back into the module parameters before the next derivation step.
+
+use serde::{Deserialize, Serialize};
+use symbios::SymbiosState;
+use symbios::core::SymbiosError;
+
+/// The turtle quantity written back by a query module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueryKind {
+    /// World-space position (`?P`).
+    Position,
+    /// Direction of travel, local +Y (`?H`).
+    Heading,
+    /// The turtle's up vector in ABOP terms: local +Z, the axis `+`/`-` turn around (`?U`).
+    Up,
+}
+
+/// Turtle state captured by a query module at `module_index`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueryPatch {
+    /// Index of the query module in the interpreted state.
+    pub module_index: usize,
+    /// Which quantity was captured.
+    pub kind: QueryKind,
+    /// The captured `(x, y, z)` values.
+    pub values: [f64; 3],
+}
+
+/// Returns a copy of `state` with each patch written into its module's parameters.
+///
+/// Patches overwrite the first parameters of the module (up to three), so the module
+/// keeps its arity and still matches the same productions. Module ages, the current
+/// time and the capacity limit are preserved. Topology links are not copied; call
+/// [`SymbiosState::calculate_topology`] again if you rely on them.
+pub fn apply_query_patches(
+    state: &SymbiosState,
+    patches: &[QueryPatch],
+) -> Result<SymbiosState, SymbiosError> {
+    // Time must be set before pushing so that ages map back to the same birth times.
+    let mut patched = SymbiosState::new();
+    patched.current_time = state.current_time;
+    patched.max_capacity = state.max_capacity;
+
+    let mut sorted: Vec<&QueryPatch> = patches.iter().collect();
+    sorted.sort_by_key(|p| p.module_index);
+    let mut next = sorted.into_iter().peekable();
+    let mut params = Vec::new();
+
+    for i in 0..state.len() {
+        let view = state.get_view(i).ok_or(SymbiosError::InvalidIndex(i))?;
+        params.clear();
+        params.extend_from_slice(view.params);
+
+        while let Some(patch) = next.next_if(|p| p.module_index <= i) {
+            if patch.module_index == i {
+                for (param, value) in params.iter_mut().zip(patch.values) {
+                    *param = value;
+                }
+            }
+        }
+        patched.push(view.sym, view.age, &params)?;
+    }
+    Ok(patched)
+}
@@ -0,0 +1,100 @@
+//! Output sinks for turtle interpretation.
+//!
+//! The interpreter reports geometry through the [`TurtleSink`] callbacks as it is
+//! produced. [`Skeleton`] is the default sink; custom sinks can stream points straight
+//! into GPU staging buffers or a scene graph without materialising a skeleton.
+
+use crate::environment::TerminatedBranch;
+use crate::skeleton::{Skeleton, SkeletonPoint, SkeletonPolygon, SkeletonProp, StrandInfo};
+
+/// Receives geometry from the interpreter.
+///
+/// Strand and point indices in [`StrandInfo`] and [`TerminatedBranch`] count the
+/// strands begun and points emitted during this interpretation, starting from zero.
+/// Points closer than the deduplication threshold to their predecessor are dropped
+/// before they reach the sink.
+pub trait TurtleSink {
+    /// Starts a new strand at `point`. Any previous strand has already been ended.
+    fn begin_strand(&mut self, point: SkeletonPoint, info: StrandInfo);
+
+    /// Appends `point` to the current strand.
+    fn add_point(&mut self, point: SkeletonPoint);
+
+    /// Ends the current strand. Called before the next strand begins and once at
+    /// the end of interpretation.
+    fn end_strand(&mut self) {}
+
+    /// Records a prop spawned with `~`.
+    fn spawn_prop(&mut self, prop: SkeletonProp);
+
+    /// Records a polygon closed with `}`.
+    fn add_polygon(&mut self, _polygon: SkeletonPolygon) {}
+
+    /// Called on `[`, after the current strand has ended and before the branch's
+    /// first strand begins.
+    fn branch_push(&mut self) {}
+
+    /// Called on `]`, after the branch's strand has ended and before the parent
+    /// resumes.
+    fn branch_pop(&mut self) {}
+
+    /// Records a branch stopped under
+    /// [`ObstaclePolicy::Terminate`](crate::ObstaclePolicy::Terminate).
+    fn terminate_branch(&mut self, _branch: TerminatedBranch) {}
+}
+
+impl TurtleSink for Skeleton {
+    fn begin_strand(&mut self, point: SkeletonPoint, info: StrandInfo) {
+        self.start_strand(point, info);
+    }
+
+    fn add_point(&mut self, point: SkeletonPoint) {
+        self.add_node(point, false);
+    }
+
+    fn spawn_prop(&mut self, prop: SkeletonProp) {
+        self.add_prop(prop);
+    }
+
+    fn add_polygon(&mut self, polygon: SkeletonPolygon) {
+        Skeleton::add_polygon(self, polygon);
+    }
+
+    fn terminate_branch(&mut self, branch: TerminatedBranch) {
+        self.terminated.push(branch);
+    }
+}
+
+impl<S: TurtleSink + ?Sized> TurtleSink for &mut S {
+    fn begin_strand(&mut self, point: SkeletonPoint, info: StrandInfo) {
+        (**self).begin_strand(point, info);
+    }
+
+    fn add_point(&mut self, point: SkeletonPoint) {
+        (**self).add_point(point);
+    }
+
+    fn end_strand(&mut self) {
+        (**self).end_strand();
+    }
+
+    fn spawn_prop(&mut self, prop: SkeletonProp) {
+        (**self).spawn_prop(prop);
+    }
+
+    fn add_polygon(&mut self, polygon: SkeletonPolygon) {
+        (**self).add_polygon(polygon);
+    }
+
+    fn branch_push(&mut self) {
+        (**self).branch_push();
+    }
+
+    fn branch_pop(&mut self) {
+        (**self).branch_pop();
+    }
+
+    fn terminate_branch(&mut self, branch: TerminatedBranch) {
+        (**self).terminate_branch(branch);
+    }
+}
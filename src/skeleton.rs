@@ -1,8 +1,13 @@
 //! Skeleton data structures representing the geometric output of turtle interpretation.
 
-use glam::{Quat, Vec3, Vec4};
+use crate::conventions::Axis;
+use crate::environment::TerminatedBranch;
+use glam::{Quat, Vec2, Vec3, Vec4};
 use serde::{Deserialize, Serialize};
 
+/// Squared distance below which a point is considered a duplicate of its predecessor.
+pub(crate) const DEDUP_DISTANCE_SQUARED: f32 = 0.00001;
+
 /// A point along a skeleton strand with position, orientation, and material properties.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct SkeletonPoint {
@@ -44,16 +49,149 @@ pub struct SkeletonProp {
     pub material_id: u8,
 }
 
-/// The geometric output of turtle interpretation: a collection of strands and props.
+/// A closed vertex loop traced by the turtle between `{` and `}` (leaves, petals).
+///
+/// Color and material ID are captured from the turtle when the polygon is opened.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SkeletonPolygon {
+    /// World-space vertices in the order they were recorded with `.`.
+    pub vertices: Vec<Vec3>,
+    /// RGBA color inherited from turtle state at `{`.
+    pub color: Vec4,
+    /// Material palette ID inherited from turtle state at `{`.
+    pub material_id: u8,
+}
+
+impl SkeletonPolygon {
+    /// Returns the unit normal of the polygon's best-fit plane (Newell's method).
+    ///
+    /// The normal follows the right-hand rule with respect to vertex order.
+    /// Returns `Vec3::ZERO` for degenerate (collinear or empty) outlines.
+    pub fn normal(&self) -> Vec3 {
+        let n = self.vertices.len();
+        let mut normal = Vec3::ZERO;
+        for i in 0..n {
+            let a = self.vertices[i];
+            let b = self.vertices[(i + 1) % n];
+            normal += Vec3::new(
+                (a.y - b.y) * (a.z + b.z),
+                (a.z - b.z) * (a.x + b.x),
+                (a.x - b.x) * (a.y + b.y),
+            );
+        }
+        normal.normalize_or_zero()
+    }
+
+    /// Triangulates the outline by ear clipping, returning indices into `vertices`.
+    ///
+    /// Handles concave outlines. Vertices are projected onto the best-fit plane, and
+    /// triangles are wound consistently with [`Self::normal`]. Degenerate polygons
+    /// yield no triangles.
+    pub fn triangulate(&self) -> Vec<[u32; 3]> {
+        let n = self.vertices.len();
+        let normal = self.normal();
+        if n < 3 || normal == Vec3::ZERO {
+            return Vec::new();
+        }
+
+        let u_axis = normal.any_orthonormal_vector();
+        let v_axis = normal.cross(u_axis);
+        let projected: Vec<Vec2> = self
+            .vertices
+            .iter()
+            .map(|p| Vec2::new(p.dot(u_axis), p.dot(v_axis)))
+            .collect();
+
+        // Projection onto (u, normal x u) preserves winding, so the loop is counter-clockwise.
+        let mut remaining: Vec<usize> = (0..n).collect();
+        let mut triangles = Vec::with_capacity(n - 2);
+
+        while remaining.len() > 3 {
+            let m = remaining.len();
+            let ear = (0..m)
+                .find(|&i| is_ear(&projected, &remaining, i))
+                .unwrap_or(0); // Self-intersecting input: clip anyway to guarantee progress
+            let prev = remaining[(ear + m - 1) % m];
+            let next = remaining[(ear + 1) % m];
+            triangles.push([prev as u32, remaining[ear] as u32, next as u32]);
+            remaining.remove(ear);
+        }
+        triangles.push([
+            remaining[0] as u32,
+            remaining[1] as u32,
+            remaining[2] as u32,
+        ]);
+        triangles
+    }
+}
+
+fn is_ear(points: &[Vec2], remaining: &[usize], i: usize) -> bool {
+    let m = remaining.len();
+    let a = points[remaining[(i + m - 1) % m]];
+    let b = points[remaining[i]];
+    let c = points[remaining[(i + 1) % m]];
+
+    if (b - a).perp_dot(c - b) <= 0.0 {
+        return false; // Reflex or collinear corner
+    }
+
+    remaining.iter().all(|&idx| {
+        let p = points[idx];
+        p == a || p == b || p == c || !point_in_triangle(p, a, b, c)
+    })
+}
+
+fn point_in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    (b - a).perp_dot(p - a) >= 0.0
+        && (c - b).perp_dot(p - b) >= 0.0
+        && (a - c).perp_dot(p - c) >= 0.0
+}
+
+/// Topology record for one strand: where it branched from and how deep it is.
+///
+/// Strands created by `[` attach to the point the branch grew from. Strands that
+/// resume the parent after `]` attach to the same point. Strands started by a
+/// Move record the strand they continue and its last point, but are marked
+/// [`detached`](Self::detached) since the two are not geometrically connected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrandInfo {
+    /// Index of the strand this one grew from, or `None` for a root strand.
+    pub parent: Option<usize>,
+    /// Index of the point on the parent strand where this strand attaches.
+    pub parent_point: usize,
+    /// Branching depth: the number of open `[` when the strand started.
+    pub depth: usize,
+    /// The strand does not start at its parent point (it was started by a Move).
+    ///
+    /// [`Skeleton::parent`] and the traversal helpers treat detached strands as roots.
+    #[serde(default)]
+    pub detached: bool,
+}
+
+/// The geometric output of turtle interpretation: a collection of strands, props and polygons.
 ///
 /// Strands are sequences of connected [`SkeletonPoint`]s representing branches/stems.
 /// Props are discrete objects spawned at specific locations.
+/// Polygons are surfaces traced with `{`, `.` and `}`.
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
 pub struct Skeleton {
     /// Connected sequences of skeleton points forming branches.
     pub strands: Vec<Vec<SkeletonPoint>>,
     /// Discrete props (leaves, flowers, etc.) spawned during interpretation.
     pub props: Vec<SkeletonProp>,
+    /// Closed vertex loops (leaves, petals) traced during interpretation.
+    #[serde(default)]
+    pub polygons: Vec<SkeletonPolygon>,
+    /// Branch topology, parallel to `strands`.
+    #[serde(default)]
+    pub strand_info: Vec<StrandInfo>,
+    /// Branches stopped by an obstacle under [`ObstaclePolicy::Terminate`](crate::ObstaclePolicy::Terminate).
+    #[serde(default)]
+    pub terminated: Vec<TerminatedBranch>,
+    /// Local axis of every point and prop rotation that points along the turtle heading,
+    /// from [`Conventions::heading`](crate::Conventions::heading).
+    #[serde(default)]
+    pub heading: Axis,
 }
 
 impl Skeleton {
@@ -65,12 +203,14 @@ impl Skeleton {
     ///
     /// If `force_new_strand` is true, starts a new strand. Otherwise appends to the current strand,
     /// unless the point is too close to the previous one (deduplication).
+    /// New strands created this way are recorded as roots; use [`Self::start_strand`]
+    /// to record their parent.
     pub fn add_node(&mut self, point: SkeletonPoint, force_new_strand: bool) {
         if force_new_strand || self.strands.is_empty() {
-            self.strands.push(vec![point]);
+            self.start_strand(point, StrandInfo::default());
         } else if let Some(last_strand) = self.strands.last_mut() {
             if let Some(last_point) = last_strand.last()
-                && last_point.position.distance_squared(point.position) < 0.00001
+                && last_point.position.distance_squared(point.position) < DEDUP_DISTANCE_SQUARED
             {
                 return;
             }
@@ -78,12 +218,124 @@ impl Skeleton {
         }
     }
 
+    /// Starts a new strand at `point` with the given topology record.
+    pub fn start_strand(&mut self, point: SkeletonPoint, info: StrandInfo) {
+        // Re-synchronise if strands were pushed directly without topology
+        self.strand_info
+            .resize(self.strands.len(), StrandInfo::default());
+        self.strands.push(vec![point]);
+        self.strand_info.push(info);
+    }
+
+    /// Returns the `(strand, point)` indices of the last point of the last strand.
+    pub fn tip(&self) -> Option<(usize, usize)> {
+        let strand = self.strands.len().checked_sub(1)?;
+        let point = self.strands[strand].len().checked_sub(1)?;
+        Some((strand, point))
+    }
+
     pub fn add_prop(&mut self, prop: SkeletonProp) {
         self.props.push(prop);
     }
 
+    pub fn add_polygon(&mut self, polygon: SkeletonPolygon) {
+        self.polygons.push(polygon);
+    }
+
     pub fn clear(&mut self) {
         self.strands.clear();
         self.props.clear();
+        self.polygons.clear();
+        self.strand_info.clear();
+        self.terminated.clear();
+    }
+
+    /// Returns the topology record of `strand`, or a root record if none was stored.
+    pub fn strand_info(&self, strand: usize) -> StrandInfo {
+        self.strand_info.get(strand).copied().unwrap_or_default()
+    }
+
+    /// Returns the strand that `strand` is attached to, if any.
+    ///
+    /// [`StrandInfo::detached`] strands have no parent here, even though their
+    /// topology record names the strand they continue.
+    pub fn parent(&self, strand: usize) -> Option<usize> {
+        let info = self.strand_info(strand);
+        info.parent.filter(|_| !info.detached)
+    }
+
+    /// Iterates over the strands that have no parent, detached strands included.
+    pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.strands.len()).filter(|&i| self.parent(i).is_none())
+    }
+
+    /// Iterates over the direct children of `strand`, in creation order.
+    pub fn children(&self, strand: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.strands.len()).filter(move |&i| self.parent(i) == Some(strand))
+    }
+
+    /// Returns the chain of strands from `strand` up to its root, inclusive.
+    pub fn path_to_root(&self, strand: usize) -> Vec<usize> {
+        let mut path = vec![strand];
+        let mut current = strand;
+        while let Some(parent) = self.parent(current) {
+            // Guard against cycles in hand-edited topology
+            if path.len() > self.strands.len() || path.contains(&parent) {
+                break;
+            }
+            path.push(parent);
+            current = parent;
+        }
+        path
+    }
+
+    /// Returns `strand` and all of its descendants in depth-first pre-order.
+    pub fn subtree(&self, strand: usize) -> Vec<usize> {
+        let mut children = vec![Vec::new(); self.strands.len()];
+        for i in 0..self.strands.len() {
+            if let Some(parent) = self.parent(i)
+                && parent < children.len()
+            {
+                children[parent].push(i);
+            }
+        }
+
+        let mut visited = vec![false; self.strands.len()];
+        let mut order = Vec::new();
+        let mut stack = vec![strand];
+        while let Some(current) = stack.pop() {
+            if current >= visited.len() || visited[current] {
+                continue;
+            }
+            visited[current] = true;
+            order.push(current);
+            stack.extend(children[current].iter().rev());
+        }
+        order
+    }
+}
+
+/// Returns the unit tangent of `strand` at point `i`, averaging adjacent segments.
+///
+/// Single-point strands fall back to the point's local `heading` axis.
+pub(crate) fn strand_tangent(strand: &[SkeletonPoint], i: usize, heading: Axis) -> Vec3 {
+    let incoming = i
+        .checked_sub(1)
+        .map(|prev| (strand[i].position - strand[prev].position).normalize_or_zero())
+        .unwrap_or(Vec3::ZERO);
+    let outgoing = strand
+        .get(i + 1)
+        .map(|next| (next.position - strand[i].position).normalize_or_zero())
+        .unwrap_or(Vec3::ZERO);
+
+    let tangent = (incoming + outgoing).normalize_or_zero();
+    if tangent != Vec3::ZERO {
+        tangent
+    } else if outgoing != Vec3::ZERO {
+        outgoing
+    } else if incoming != Vec3::ZERO {
+        incoming
+    } else {
+        strand[i].rotation * heading.unit()
     }
 }
@@ -0,0 +1,171 @@
+//! Spline interpolation of skeleton strands.
+//!
+//! Re-emits each strand with extra points sampled from a Catmull-Rom or cubic
+//! B-spline, so low step-count L-systems produce smooth curves without deeper
+//! derivations. Strands are interpolated independently, so breaks created by
+//! Push, Pop and Move are preserved.
+
+use crate::skeleton::{Skeleton, SkeletonPoint, StrandInfo};
+use glam::Vec4;
+
+/// Spline family used by [`Skeleton::smoothed`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SplineKind {
+    /// Centripetal Catmull-Rom: passes through every original point without cusps.
+    #[default]
+    CatmullRom,
+    /// Uniform cubic B-spline: smoother (C2), but only approximates interior points.
+    /// Strand endpoints are kept fixed.
+    BSpline,
+}
+
+/// Configuration for [`Skeleton::smoothed`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SmoothingConfig {
+    /// Spline used for position and radius.
+    pub kind: SplineKind,
+    /// Number of interpolated points inserted between each pair of original points.
+    pub points_per_segment: u32,
+}
+
+impl Default for SmoothingConfig {
+    fn default() -> Self {
+        Self {
+            kind: SplineKind::CatmullRom,
+            points_per_segment: 3,
+        }
+    }
+}
+
+impl Skeleton {
+    /// Returns a copy of the skeleton with every strand resampled along a spline.
+    ///
+    /// Position and radius follow the spline, color is interpolated linearly and
+    /// orientation is slerped. Interpolated points take material ID and UV scale from
+    /// the end of their segment. Topology is remapped so that child strands still
+    /// attach to the corresponding point on their parent; with
+    /// [`SplineKind::BSpline`] the first point of each child is moved onto its
+    /// parent's smoothed attachment point. Props, polygons and terminated
+    /// branches are copied unchanged.
+    pub fn smoothed(&self, config: &SmoothingConfig) -> Skeleton {
+        let stride = config.points_per_segment as usize + 1;
+        let mut strands: Vec<Vec<SkeletonPoint>> = vec![Vec::new(); self.strands.len()];
+        let mut strand_info = self.strand_info.clone();
+        strand_info.resize(self.strands.len(), StrandInfo::default());
+
+        // Parents are smoothed before their children so junctions can be re-welded.
+        // Strands unreachable from a root (inconsistent topology) are appended last.
+        let mut order = Vec::with_capacity(self.strands.len());
+        for root in self.roots().collect::<Vec<_>>() {
+            order.extend(self.subtree(root));
+        }
+        let mut visited = vec![false; self.strands.len()];
+        for &s in &order {
+            visited[s] = true;
+        }
+        order.extend((0..self.strands.len()).filter(|&s| !visited[s]));
+
+        for s in order {
+            let mut control = self.strands[s].clone();
+            let info = &mut strand_info[s];
+            if info.parent.is_some() {
+                info.parent_point *= stride;
+            }
+            if let Some(parent) = info.parent.filter(|_| !info.detached)
+                && let Some(anchor) = strands.get(parent).and_then(|p| p.get(info.parent_point))
+                && let Some(first) = control.first_mut()
+            {
+                first.position = anchor.position;
+            }
+            strands[s] = resample(&control, config.kind, stride);
+        }
+
+        Skeleton {
+            strands,
+            props: self.props.clone(),
+            polygons: self.polygons.clone(),
+            strand_info,
+            terminated: self.terminated.clone(),
+            heading: self.heading,
+        }
+    }
+}
+
+fn resample(control: &[SkeletonPoint], kind: SplineKind, stride: usize) -> Vec<SkeletonPoint> {
+    let n = control.len();
+    if n < 2 || stride == 1 {
+        return control.to_vec();
+    }
+
+    // Pack position and radius so both follow the same spline.
+    let packed = |p: &SkeletonPoint| p.position.extend(p.radius);
+    let get = |i: isize| -> Vec4 {
+        // Reflected phantom points keep the tangent natural at strand ends and
+        // make the B-spline interpolate the first and last points.
+        if i < 0 {
+            2.0 * packed(&control[0]) - packed(&control[1])
+        } else if i as usize >= n {
+            2.0 * packed(&control[n - 1]) - packed(&control[n - 2])
+        } else {
+            packed(&control[i as usize])
+        }
+    };
+
+    let mut out = Vec::with_capacity((n - 1) * stride + 1);
+    for seg in 0..n - 1 {
+        let i = seg as isize;
+        let (p0, p1, p2, p3) = (get(i - 1), get(i), get(i + 1), get(i + 2));
+        let (a, b) = (&control[seg], &control[seg + 1]);
+
+        for k in 0..stride {
+            let u = k as f32 / stride as f32;
+            let value = match kind {
+                SplineKind::CatmullRom => catmull_rom(p0, p1, p2, p3, u),
+                SplineKind::BSpline => b_spline(p0, p1, p2, p3, u),
+            };
+            let source = if k == 0 { a } else { b };
+            out.push(SkeletonPoint {
+                position: value.truncate(),
+                rotation: a.rotation.slerp(b.rotation, u),
+                radius: value.w.max(0.0),
+                color: a.color.lerp(b.color, u),
+                material_id: source.material_id,
+                uv_scale: source.uv_scale,
+            });
+        }
+    }
+    out.push(control[n - 1]);
+    out
+}
+
+/// Centripetal Catmull-Rom (Barry-Goldman) evaluated between `p1` and `p2`.
+fn catmull_rom(p0: Vec4, p1: Vec4, p2: Vec4, p3: Vec4, u: f32) -> Vec4 {
+    // Knot spacing uses positional distance only; radius rides along.
+    let knot = |a: Vec4, b: Vec4| (b - a).truncate().length().sqrt().max(1e-4);
+    let t0 = 0.0;
+    let t1 = t0 + knot(p0, p1);
+    let t2 = t1 + knot(p1, p2);
+    let t3 = t2 + knot(p2, p3);
+    let t = t1 + (t2 - t1) * u;
+
+    let lerp = |a: Vec4, b: Vec4, ta: f32, tb: f32| {
+        a * ((tb - t) / (tb - ta)) + b * ((t - ta) / (tb - ta))
+    };
+    let a1 = lerp(p0, p1, t0, t1);
+    let a2 = lerp(p1, p2, t1, t2);
+    let a3 = lerp(p2, p3, t2, t3);
+    let b1 = lerp(a1, a2, t0, t2);
+    let b2 = lerp(a2, a3, t1, t3);
+    lerp(b1, b2, t1, t2)
+}
+
+/// Uniform cubic B-spline evaluated on the span between `p1` and `p2`.
+fn b_spline(p0: Vec4, p1: Vec4, p2: Vec4, p3: Vec4, u: f32) -> Vec4 {
+    let u2 = u * u;
+    let u3 = u2 * u;
+    let b0 = (1.0 - u).powi(3) / 6.0;
+    let b1 = (3.0 * u3 - 6.0 * u2 + 4.0) / 6.0;
+    let b2 = (-3.0 * u3 + 3.0 * u2 + 3.0 * u + 1.0) / 6.0;
+    let b3 = u3 / 6.0;
+    p0 * b0 + p1 * b1 + p2 * b2 + p3 * b3
+}
@@ -0,0 +1,429 @@
+//! Bounding-volume hierarchy and geometric queries over a [`Skeleton`].
+//!
+//! Segments between consecutive [`SkeletonPoint`](crate::SkeletonPoint)s are treated
+//! as capsules and props as spheres, so picking, collision and proximity queries do
+//! not have to test every segment.
+
+use crate::skeleton::Skeleton;
+use glam::Vec3;
+
+/// Maximum number of primitives stored in a BVH leaf.
+const LEAF_SIZE: usize = 4;
+
+/// An axis-aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// An inverted box that contains nothing; the identity for [`Aabb::union`].
+    pub const EMPTY: Self = Self {
+        min: Vec3::INFINITY,
+        max: Vec3::NEG_INFINITY,
+    };
+
+    /// Returns true if the box contains no points.
+    pub fn is_empty(&self) -> bool {
+        self.min.cmpgt(self.max).any()
+    }
+
+    /// Returns the smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Returns the centre of the box.
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Returns the squared distance from `point` to the box (zero inside).
+    pub fn distance_squared(&self, point: Vec3) -> f32 {
+        let clamped = point.clamp(self.min, self.max);
+        clamped.distance_squared(point)
+    }
+
+    /// Returns the ray distance at which the ray enters the box, if it does
+    /// within `max_distance`.
+    fn ray_entry(&self, origin: Vec3, inv_dir: Vec3, max_distance: f32) -> Option<f32> {
+        let t1 = (self.min - origin) * inv_dir;
+        let t2 = (self.max - origin) * inv_dir;
+        let t_near = t1.min(t2).max_element().max(0.0);
+        let t_far = t1.max(t2).min_element().min(max_distance);
+        (t_near <= t_far).then_some(t_near)
+    }
+
+    fn from_capsule(a: Vec3, b: Vec3, radius: f32) -> Aabb {
+        Aabb {
+            min: a.min(b) - Vec3::splat(radius),
+            max: a.max(b) + Vec3::splat(radius),
+        }
+    }
+}
+
+/// A sphere enclosing every primitive in a [`SkeletonBvh`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+/// Identifies the skeleton element behind a query result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpatialElement {
+    /// The segment from `strands[strand][point]` to `strands[strand][point + 1]`.
+    Segment { strand: usize, point: usize },
+    /// The prop at `props[index]`.
+    Prop { index: usize },
+}
+
+/// Result of [`SkeletonBvh::raycast`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    /// The element that was hit.
+    pub element: SpatialElement,
+    /// Distance along the (normalized) ray to the hit.
+    pub distance: f32,
+    /// Parametric position of the hit along the segment axis, in `[0, 1]`
+    /// (always `0` for props).
+    pub t: f32,
+}
+
+/// Result of [`SkeletonBvh::nearest`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NearestHit {
+    /// The nearest element.
+    pub element: SpatialElement,
+    /// Distance from the query point to the element's surface (zero inside).
+    pub distance: f32,
+    /// Parametric position of the closest axis point along the segment, in `[0, 1]`.
+    pub t: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Primitive {
+    element: SpatialElement,
+    a: Vec3,
+    b: Vec3,
+    radius: f32,
+    bounds: Aabb,
+}
+
+impl Primitive {
+    /// Returns the axis parameter and surface distance closest to `point`.
+    fn closest(&self, point: Vec3) -> (f32, f32) {
+        let t = segment_param(self.a, self.b, point);
+        let axis = self.a.lerp(self.b, t);
+        (t, (axis.distance(point) - self.radius).max(0.0))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    bounds: Aabb,
+    /// Leaf: index of the first primitive. Interior: index of the left child
+    /// (the right child is `left + 1`).
+    start: usize,
+    /// Number of primitives in a leaf; zero for interior nodes.
+    count: usize,
+}
+
+/// A bounding-volume hierarchy over skeleton segments and props.
+///
+/// Segments are capsules with the larger of their two endpoint radii; props are
+/// spheres whose radius is the largest component of their scale (i.e. unit-radius
+/// assets). The hierarchy owns copies of the geometry, so it stays valid if the
+/// skeleton is dropped, but must be rebuilt after the skeleton changes.
+#[derive(Clone, Debug, Default)]
+pub struct SkeletonBvh {
+    primitives: Vec<Primitive>,
+    nodes: Vec<Node>,
+}
+
+impl SkeletonBvh {
+    /// Builds a hierarchy over every segment and prop of `skeleton`.
+    pub fn build(skeleton: &Skeleton) -> Self {
+        let mut primitives = Vec::new();
+        for (s, strand) in skeleton.strands.iter().enumerate() {
+            for (i, pair) in strand.windows(2).enumerate() {
+                let radius = pair[0].radius.max(pair[1].radius).max(0.0);
+                primitives.push(Primitive {
+                    element: SpatialElement::Segment {
+                        strand: s,
+                        point: i,
+                    },
+                    a: pair[0].position,
+                    b: pair[1].position,
+                    radius,
+                    bounds: Aabb::from_capsule(pair[0].position, pair[1].position, radius),
+                });
+            }
+        }
+        for (index, prop) in skeleton.props.iter().enumerate() {
+            let radius = prop.scale.abs().max_element();
+            primitives.push(Primitive {
+                element: SpatialElement::Prop { index },
+                a: prop.position,
+                b: prop.position,
+                radius,
+                bounds: Aabb::from_capsule(prop.position, prop.position, radius),
+            });
+        }
+
+        let mut bvh = Self {
+            primitives,
+            nodes: Vec::new(),
+        };
+        if !bvh.primitives.is_empty() {
+            bvh.nodes.push(Node {
+                bounds: Aabb::EMPTY,
+                start: 0,
+                count: 0,
+            });
+            bvh.subdivide(0, 0, bvh.primitives.len());
+        }
+        bvh
+    }
+
+    fn subdivide(&mut self, node: usize, start: usize, end: usize) {
+        let prims = &mut self.primitives[start..end];
+        let bounds = prims
+            .iter()
+            .fold(Aabb::EMPTY, |acc, p| acc.union(&p.bounds));
+        self.nodes[node].bounds = bounds;
+
+        if prims.len() <= LEAF_SIZE {
+            self.nodes[node].start = start;
+            self.nodes[node].count = prims.len();
+            return;
+        }
+
+        // Median split along the longest axis of the centroid bounds.
+        let centroids = prims.iter().fold(Aabb::EMPTY, |acc, p| {
+            let c = p.bounds.center();
+            acc.union(&Aabb { min: c, max: c })
+        });
+        let extent = centroids.max - centroids.min;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+        let mid = prims.len() / 2;
+        prims.select_nth_unstable_by(mid, |a, b| {
+            a.bounds.center()[axis].total_cmp(&b.bounds.center()[axis])
+        });
+
+        let left = self.nodes.len();
+        let placeholder = Node {
+            bounds: Aabb::EMPTY,
+            start: 0,
+            count: 0,
+        };
+        self.nodes.push(placeholder);
+        self.nodes.push(placeholder);
+        self.nodes[node].start = left;
+        self.nodes[node].count = 0;
+        self.subdivide(left, start, start + mid);
+        self.subdivide(left + 1, start + mid, end);
+    }
+
+    /// Returns the number of primitives (segments and props) in the hierarchy.
+    pub fn len(&self) -> usize {
+        self.primitives.len()
+    }
+
+    /// Returns true if the hierarchy contains no primitives.
+    pub fn is_empty(&self) -> bool {
+        self.primitives.is_empty()
+    }
+
+    /// Returns the axis-aligned bounds of every primitive, including radii.
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.nodes.first().map(|n| n.bounds)
+    }
+
+    /// Returns a sphere enclosing every primitive, centred on the bounds.
+    pub fn bounding_sphere(&self) -> Option<BoundingSphere> {
+        let center = self.bounds()?.center();
+        let radius = self
+            .primitives
+            .iter()
+            .map(|p| center.distance(p.a).max(center.distance(p.b)) + p.radius)
+            .fold(0.0, f32::max);
+        Some(BoundingSphere { center, radius })
+    }
+
+    /// Casts a ray and returns the closest hit within `max_distance`.
+    ///
+    /// `direction` need not be normalized; distances are measured along its unit vector.
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<RayHit> {
+        let dir = direction.normalize_or_zero();
+        if dir == Vec3::ZERO || self.nodes.is_empty() {
+            return None;
+        }
+        let inv_dir = dir.recip();
+
+        let mut best: Option<RayHit> = None;
+        let mut limit = max_distance;
+        let mut stack = vec![0];
+        while let Some(idx) = stack.pop() {
+            let node = self.nodes[idx];
+            if node.bounds.ray_entry(origin, inv_dir, limit).is_none() {
+                continue;
+            }
+            if node.count == 0 {
+                stack.push(node.start);
+                stack.push(node.start + 1);
+                continue;
+            }
+            for prim in &self.primitives[node.start..node.start + node.count] {
+                if let Some(distance) = ray_capsule(origin, dir, prim.a, prim.b, prim.radius)
+                    && distance <= limit
+                {
+                    limit = distance;
+                    best = Some(RayHit {
+                        element: prim.element,
+                        distance,
+                        t: segment_param(prim.a, prim.b, origin + dir * distance),
+                    });
+                }
+            }
+        }
+        best
+    }
+
+    /// Returns the element whose surface is closest to `point`.
+    pub fn nearest(&self, point: Vec3) -> Option<NearestHit> {
+        let mut best: Option<NearestHit> = None;
+        let mut best_sq = f32::INFINITY;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(idx) = stack.pop() {
+            let node = self.nodes[idx];
+            if node.bounds.distance_squared(point) > best_sq {
+                continue;
+            }
+            if node.count == 0 {
+                // Visit the nearer child last so it is popped first.
+                let (l, r) = (node.start, node.start + 1);
+                let dl = self.nodes[l].bounds.distance_squared(point);
+                let dr = self.nodes[r].bounds.distance_squared(point);
+                if dl < dr {
+                    stack.extend([r, l]);
+                } else {
+                    stack.extend([l, r]);
+                }
+                continue;
+            }
+            for prim in &self.primitives[node.start..node.start + node.count] {
+                let (t, distance) = prim.closest(point);
+                if best.is_none() || distance < best.map_or(f32::INFINITY, |b| b.distance) {
+                    best_sq = distance * distance;
+                    best = Some(NearestHit {
+                        element: prim.element,
+                        distance,
+                        t,
+                    });
+                }
+            }
+        }
+        best
+    }
+
+    /// Returns every element whose surface lies within `radius` of `point`.
+    pub fn within_radius(&self, point: Vec3, radius: f32) -> Vec<SpatialElement> {
+        let mut found = Vec::new();
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        let radius_sq = radius * radius;
+        while let Some(idx) = stack.pop() {
+            let node = self.nodes[idx];
+            if node.bounds.distance_squared(point) > radius_sq {
+                continue;
+            }
+            if node.count == 0 {
+                stack.extend([node.start, node.start + 1]);
+                continue;
+            }
+            for prim in &self.primitives[node.start..node.start + node.count] {
+                if prim.closest(point).1 <= radius {
+                    found.push(prim.element);
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Returns the parameter in `[0, 1]` of the point on segment `ab` closest to `p`.
+fn segment_param(a: Vec3, b: Vec3, p: Vec3) -> f32 {
+    let ab = b - a;
+    let len_sq = ab.length_squared();
+    if len_sq < f32::EPSILON {
+        0.0
+    } else {
+        ((p - a).dot(ab) / len_sq).clamp(0.0, 1.0)
+    }
+}
+
+/// Ray/capsule intersection; `dir` must be normalized. Returns the entry distance.
+fn ray_capsule(origin: Vec3, dir: Vec3, a: Vec3, b: Vec3, radius: f32) -> Option<f32> {
+    let ba = b - a;
+    let oa = origin - a;
+    let baba = ba.dot(ba);
+    let bard = ba.dot(dir);
+    let baoa = ba.dot(oa);
+    let rdoa = dir.dot(oa);
+    let oaoa = oa.dot(oa);
+
+    let qa = baba - bard * bard;
+    if qa > f32::EPSILON * baba.max(1.0) {
+        let qb = baba * rdoa - baoa * bard;
+        let qc = baba * oaoa - baoa * baoa - radius * radius * baba;
+        let h = qb * qb - qa * qc;
+        if h < 0.0 {
+            return None;
+        }
+        let t = (-qb - h.sqrt()) / qa;
+        let y = baoa + t * bard;
+        if y > 0.0 && y < baba {
+            return (t >= 0.0).then_some(t);
+        }
+        // Otherwise the hit, if any, is on one of the hemispherical caps.
+        let cap = if y <= 0.0 { a } else { b };
+        return ray_sphere(origin, dir, cap, radius);
+    }
+
+    // Ray parallel to the axis (or a degenerate segment): only the caps can be entered first.
+    [
+        ray_sphere(origin, dir, a, radius),
+        ray_sphere(origin, dir, b, radius),
+    ]
+    .into_iter()
+    .flatten()
+    .min_by(f32::total_cmp)
+}
+
+fn ray_sphere(origin: Vec3, dir: Vec3, center: Vec3, radius: f32) -> Option<f32> {
+    let oc = origin - center;
+    let b = oc.dot(dir);
+    let c = oc.dot(oc) - radius * radius;
+    let h = b * b - c;
+    if h < 0.0 {
+        return None;
+    }
+    let t = -b - h.sqrt();
+    (t >= 0.0).then_some(t)
+}
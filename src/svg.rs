@@ -0,0 +1,284 @@
+//! SVG export through an orthographic 2D projection.
+//!
+//! Projects a [`Skeleton`] onto a plane spanned by two world axes, or through a camera
+//! view matrix, and writes strands as polylines stroked with each point's radius and
+//! color. Props become circular markers and polygons filled shapes, so classic 2D
+//! L-systems can be viewed without building a mesh.
+
+use crate::conventions::Axis;
+use crate::skeleton::Skeleton;
+use glam::{Mat4, Vec2, Vec3, Vec4};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Maps world space onto the SVG plane.
+#[derive(Clone, Copy, Debug)]
+pub enum Projection {
+    /// Orthographic projection onto the plane spanned by two world axes, `right`
+    /// pointing right and `up` pointing up in the image.
+    Plane { right: Axis, up: Axis },
+    /// Orthographic projection through a view matrix (e.g. [`Mat4::look_at_rh`]):
+    /// view-space X points right and Y points up, and depth is discarded.
+    View(Mat4),
+}
+
+impl Projection {
+    /// The XY plane, as drawn by classic 2D L-systems under the default conventions.
+    pub const FRONT: Self = Projection::Plane {
+        right: Axis::X,
+        up: Axis::Y,
+    };
+
+    /// Projects `point` with the image's Y pointing up.
+    fn project(&self, point: Vec3) -> Vec2 {
+        match self {
+            Projection::Plane { right, up } => {
+                Vec2::new(point.dot(right.unit()), point.dot(up.unit()))
+            }
+            Projection::View(view) => view.transform_point3(point).truncate(),
+        }
+    }
+
+    /// Scale applied to world-space lengths, such as radii.
+    fn scale(&self) -> f32 {
+        match self {
+            Projection::Plane { .. } => 1.0,
+            Projection::View(view) => view.x_axis.truncate().length(),
+        }
+    }
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Self::FRONT
+    }
+}
+
+/// Configuration for SVG export.
+#[derive(Clone, Copy, Debug)]
+pub struct SvgConfig {
+    pub projection: Projection,
+    /// Multiplies every stroke width (twice the point radius).
+    pub stroke_scale: f32,
+    /// Lower bound on stroke widths, in projected units, so thin strands stay visible.
+    pub min_stroke_width: f32,
+    /// Radius of prop markers before scaling by the prop's largest scale component.
+    /// Markers are not drawn when `0`.
+    pub prop_marker_radius: f32,
+    /// Draw polygons as filled shapes.
+    pub polygons: bool,
+    /// Empty space around the drawing, as a fraction of its larger extent.
+    pub margin: f32,
+    /// Width of the image in pixels; the height follows the drawing's aspect ratio.
+    pub width: f32,
+    /// Background fill; transparent when `None`.
+    pub background: Option<Vec4>,
+}
+
+impl Default for SvgConfig {
+    fn default() -> Self {
+        Self {
+            projection: Projection::default(),
+            stroke_scale: 1.0,
+            min_stroke_width: 0.0,
+            prop_marker_radius: 0.1,
+            polygons: true,
+            margin: 0.05,
+            width: 800.0,
+            background: None,
+        }
+    }
+}
+
+/// Writes `skeleton` as an SVG image to `out`.
+///
+/// Polygons are drawn first, then strands, then prop markers, each in skeleton order.
+/// Consecutive segments of a strand with the same width and color share one
+/// `<polyline>`; a segment takes the radius and color of its end point, as in the
+/// tube mesh. Markers carry the classes `prop` and `prop-<id>` for styling.
+pub fn write_svg(skeleton: &Skeleton, config: &SvgConfig, out: &mut impl Write) -> io::Result<()> {
+    let projection = &config.projection;
+    let stroke_width = |radius: f32| {
+        (2.0 * radius * projection.scale() * config.stroke_scale).max(config.min_stroke_width)
+    };
+    // Image coordinates have Y pointing down
+    let image = |point: Vec3| {
+        let p = projection.project(point);
+        Vec2::new(p.x, -p.y)
+    };
+
+    let mut bounds = Bounds::default();
+    let mut body = String::new();
+
+    if config.polygons {
+        for polygon in &skeleton.polygons {
+            if polygon.vertices.len() < 3 {
+                continue;
+            }
+            let points: Vec<Vec2> = polygon.vertices.iter().map(|&v| image(v)).collect();
+            points.iter().for_each(|&p| bounds.include(p, 0.0));
+            let _ = writeln!(
+                body,
+                r#"<polygon points="{}" fill="{}"{}/>"#,
+                point_list(&points),
+                hex_color(polygon.color),
+                opacity("fill-opacity", polygon.color),
+            );
+        }
+    }
+
+    for strand in &skeleton.strands {
+        let mut run: Vec<Vec2> = Vec::new();
+        let mut style = None;
+        for pair in strand.windows(2) {
+            let end = &pair[1];
+            let segment_style = (stroke_width(end.radius), end.color);
+            if style != Some(segment_style) {
+                if let Some((width, color)) = style {
+                    write_polyline(&mut body, &run, width, color);
+                }
+                run = vec![image(pair[0].position)];
+                style = Some(segment_style);
+            }
+            let point = image(end.position);
+            bounds.include(run[run.len() - 1], segment_style.0 / 2.0);
+            bounds.include(point, segment_style.0 / 2.0);
+            run.push(point);
+        }
+        if let Some((width, color)) = style {
+            write_polyline(&mut body, &run, width, color);
+        }
+    }
+
+    if config.prop_marker_radius > 0.0 {
+        for prop in &skeleton.props {
+            let center = image(prop.position);
+            let radius = config.prop_marker_radius * prop.scale.max_element() * projection.scale();
+            bounds.include(center, radius);
+            let _ = writeln!(
+                body,
+                r#"<circle class="prop prop-{}" cx="{}" cy="{}" r="{}" fill="{}"{}/>"#,
+                prop.prop_id,
+                center.x,
+                center.y,
+                radius,
+                hex_color(prop.color),
+                opacity("fill-opacity", prop.color),
+            );
+        }
+    }
+
+    let (min, size) = bounds.view_box(config.margin);
+    let height = config.width * size.y / size.x;
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        config.width, height, min.x, min.y, size.x, size.y
+    )?;
+    if let Some(background) = config.background {
+        writeln!(
+            out,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"{}/>"#,
+            min.x,
+            min.y,
+            size.x,
+            size.y,
+            hex_color(background),
+            opacity("fill-opacity", background),
+        )?;
+    }
+    out.write_all(body.as_bytes())?;
+    writeln!(out, "</svg>")
+}
+
+/// Writes `skeleton` as an SVG file at `path`.
+pub fn save_svg(skeleton: &Skeleton, config: &SvgConfig, path: impl AsRef<Path>) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_svg(skeleton, config, &mut out)?;
+    out.flush()
+}
+
+fn write_polyline(body: &mut String, points: &[Vec2], width: f32, color: Vec4) {
+    let _ = writeln!(
+        body,
+        r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round"{}/>"#,
+        point_list(points),
+        hex_color(color),
+        width,
+        opacity("stroke-opacity", color),
+    );
+}
+
+fn point_list(points: &[Vec2]) -> String {
+    let mut list = String::new();
+    for (i, p) in points.iter().enumerate() {
+        if i > 0 {
+            list.push(' ');
+        }
+        let _ = write!(list, "{},{}", p.x, p.y);
+    }
+    list
+}
+
+/// Formats the RGB channels of `color` as `#rrggbb`.
+fn hex_color(color: Vec4) -> String {
+    let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(color.x),
+        channel(color.y),
+        channel(color.z)
+    )
+}
+
+/// Returns an opacity attribute for translucent colors, or nothing when opaque.
+fn opacity(attribute: &str, color: Vec4) -> String {
+    if color.w < 1.0 {
+        format!(r#" {attribute}="{}""#, color.w.max(0.0))
+    } else {
+        String::new()
+    }
+}
+
+/// Smallest ratio of the view box's shorter side to its longer one.
+const MIN_ASPECT: f32 = 0.05;
+
+/// Extent below which a drawing is treated as a single point.
+const MIN_EXTENT: f32 = 1e-6;
+
+/// Bounding box of the drawing in image coordinates.
+#[derive(Default)]
+struct Bounds {
+    range: Option<(Vec2, Vec2)>,
+}
+
+impl Bounds {
+    /// Grows the box to contain a disc of `radius` around `point`.
+    fn include(&mut self, point: Vec2, radius: f32) {
+        let (lo, hi) = (point - radius, point + radius);
+        self.range = Some(match self.range {
+            Some((min, max)) => (min.min(lo), max.max(hi)),
+            None => (lo, hi),
+        });
+    }
+
+    /// Returns the origin and size of the view box, padded by `margin` times the larger
+    /// extent. Axes thinner than [`MIN_ASPECT`] times the larger one are widened about
+    /// their center, so the image height stays bounded; empty drawings, and drawings
+    /// smaller than [`MIN_EXTENT`], get a unit box.
+    fn view_box(&self, margin: f32) -> (Vec2, Vec2) {
+        let Some((min, max)) = self.range else {
+            return (Vec2::ZERO, Vec2::ONE);
+        };
+        let center = (min + max) / 2.0;
+        let extent = (max - min).max_element();
+        if extent <= MIN_EXTENT {
+            return (center - 0.5, Vec2::ONE);
+        }
+        let size = max - min + 2.0 * extent * margin;
+        let size = size.max(Vec2::splat(size.max_element() * MIN_ASPECT));
+        (center - size / 2.0, size)
+    }
+}
@@ -0,0 +1,249 @@
+//! SWC morphology import and export.
+//!
+//! SWC stores a tree as one sample per line: `id type x y z radius parent`, with a
+//! parent of `-1` for roots. It is the exchange format of neuron-simulation and
+//! morphology tools (NEURON, NeuroMorpho.Org), so synthetic dendrites and vasculature
+//! can be simulated, and measured reconstructions meshed like any other skeleton.
+//!
+//! A sample's SWC type is the point's `material_id`, and strand parenting maps to
+//! parent links. Only strands, radii and material IDs are stored; colors, UV scales,
+//! rotations, props and polygons are not.
+
+use crate::skeleton::{Skeleton, SkeletonPoint, StrandInfo};
+use glam::{Quat, Vec3, Vec4};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Writes `skeleton` as SWC to `out`.
+///
+/// Samples are numbered from 1 in strand order. An attached strand continues from its
+/// parent's sample instead of repeating its first point, so the start radius of a
+/// branch is not stored. Root strands and [detached](crate::StrandInfo::detached)
+/// strands (started by a Move) become SWC roots.
+pub fn write_swc(skeleton: &Skeleton, out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "# symbios-turtle-3d")?;
+    writeln!(out, "# id type x y z radius parent")?;
+
+    // Sample IDs of every strand point; a skipped first point takes its parent's ID
+    let mut ids: Vec<Vec<usize>> = Vec::with_capacity(skeleton.strands.len());
+    let mut next_id = 1;
+    for (strand, points) in skeleton.strands.iter().enumerate() {
+        let parent_point = skeleton.strand_info(strand).parent_point;
+        let attachment = skeleton
+            .parent(strand)
+            .filter(|&p| p < strand)
+            .and_then(|p| ids[p].get(parent_point));
+
+        let mut strand_ids = Vec::with_capacity(points.len());
+        let mut parent = -1;
+        for (i, point) in points.iter().enumerate() {
+            if i == 0
+                && let Some(&id) = attachment
+            {
+                strand_ids.push(id);
+                parent = id as i64;
+                continue;
+            }
+            let p = point.position;
+            writeln!(
+                out,
+                "{} {} {} {} {} {} {}",
+                next_id, point.material_id, p.x, p.y, p.z, point.radius, parent
+            )?;
+            strand_ids.push(next_id);
+            parent = next_id as i64;
+            next_id += 1;
+        }
+        ids.push(strand_ids);
+    }
+    Ok(())
+}
+
+/// Writes `skeleton` as an SWC file at `path`.
+pub fn save_swc(skeleton: &Skeleton, path: impl AsRef<Path>) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_swc(skeleton, &mut out)?;
+    out.flush()
+}
+
+/// Reads an SWC morphology into a [`Skeleton`].
+///
+/// Every root starts a strand, and at each sample the first child in file order
+/// continues the strand while the others start branches that attach to it. A branch
+/// begins with a copy of its attachment sample, as strands built by the turtle do.
+/// Samples may appear in any order. Points are white with a UV scale of 1, and
+/// rotations are rotation-minimizing frames whose heading follows the strand.
+///
+/// Fails with [`io::ErrorKind::InvalidData`] on malformed lines, duplicate IDs, types
+/// outside `0..=255`, missing parents or cycles.
+pub fn read_swc(input: impl BufRead) -> io::Result<Skeleton> {
+    let mut samples: Vec<Sample> = Vec::new();
+    let mut index_of: HashMap<i64, usize> = HashMap::new();
+
+    for (line_index, line) in input.lines().enumerate() {
+        let line = line?;
+        let line_number = line_index + 1;
+        let content = line.split('#').next().unwrap_or_default().trim();
+        if content.is_empty() {
+            continue;
+        }
+        let sample = Sample::parse(content).map_err(|message| invalid(line_number, message))?;
+        if index_of.insert(sample.id, samples.len()).is_some() {
+            return Err(invalid(
+                line_number,
+                format!("duplicate sample id {}", sample.id),
+            ));
+        }
+        samples.push(Sample {
+            line: line_number,
+            ..sample
+        });
+    }
+
+    // Children of every sample in file order, and the roots
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); samples.len()];
+    let mut roots = Vec::new();
+    for (index, sample) in samples.iter().enumerate() {
+        if sample.parent < 0 {
+            roots.push(index);
+            continue;
+        }
+        let parent = *index_of.get(&sample.parent).ok_or_else(|| {
+            invalid(
+                sample.line,
+                format!("parent {} does not exist", sample.parent),
+            )
+        })?;
+        children[parent].push(index);
+    }
+
+    let mut skeleton = Skeleton::new();
+    let mut visited = 0;
+    // Pending strands as (first sample, attachment sample, topology record)
+    let mut pending: Vec<(usize, Option<usize>, StrandInfo)> = roots
+        .iter()
+        .rev()
+        .map(|&root| (root, None, StrandInfo::default()))
+        .collect();
+    while let Some((first, attachment, info)) = pending.pop() {
+        let strand = skeleton.strands.len();
+        let start = attachment.unwrap_or(first);
+        skeleton.start_strand(samples[start].point(), info);
+        if attachment.is_some() {
+            skeleton.strands[strand].push(samples[first].point());
+        }
+
+        let mut branches = Vec::new();
+        let mut current = first;
+        loop {
+            visited += 1;
+            let point = skeleton.strands[strand].len() - 1;
+            let Some((&next, rest)) = children[current].split_first() else {
+                break;
+            };
+            for &child in rest {
+                let branch = StrandInfo {
+                    parent: Some(strand),
+                    parent_point: point,
+                    depth: info.depth + 1,
+                    detached: false,
+                };
+                branches.push((child, Some(current), branch));
+            }
+            skeleton.strands[strand].push(samples[next].point());
+            current = next;
+        }
+        pending.extend(branches.into_iter().rev());
+    }
+
+    // Samples unreachable from any root lie on a cycle
+    if visited < samples.len() {
+        let line = samples
+            .iter()
+            .enumerate()
+            .find(|&(index, _)| !reaches_root(&samples, &index_of, index))
+            .map_or(0, |(_, sample)| sample.line);
+        return Err(invalid(line, "parent links form a cycle".to_string()));
+    }
+
+    skeleton.apply_rotation_minimizing_frames();
+    Ok(skeleton)
+}
+
+/// Reads the SWC file at `path` into a [`Skeleton`].
+pub fn load_swc(path: impl AsRef<Path>) -> io::Result<Skeleton> {
+    read_swc(BufReader::new(File::open(path)?))
+}
+
+fn invalid(line: usize, message: String) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("SWC line {line}: {message}"),
+    )
+}
+
+/// Returns `true` if following parent links from `index` ends at a root.
+fn reaches_root(samples: &[Sample], index_of: &HashMap<i64, usize>, mut index: usize) -> bool {
+    for _ in 0..=samples.len() {
+        match index_of.get(&samples[index].parent) {
+            Some(&parent) => index = parent,
+            None => return true,
+        }
+    }
+    false
+}
+
+/// One SWC line.
+struct Sample {
+    line: usize,
+    id: i64,
+    material_id: u8,
+    position: Vec3,
+    radius: f32,
+    parent: i64,
+}
+
+impl Sample {
+    fn parse(content: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = content.split_whitespace().collect();
+        // Some tools append extra columns, which are ignored
+        if fields.len() < 7 {
+            return Err(format!("expected 7 fields, found {}", fields.len()));
+        }
+        let float = |i: usize, name: &str| {
+            fields[i]
+                .parse::<f32>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| format!("invalid {name} {:?}", fields[i]))
+        };
+        let integer = |i: usize, name: &str| {
+            fields[i]
+                .parse::<i64>()
+                .map_err(|_| format!("invalid {name} {:?}", fields[i]))
+        };
+
+        let kind = integer(1, "type")?;
+        Ok(Self {
+            line: 0,
+            id: integer(0, "id")?,
+            material_id: u8::try_from(kind).map_err(|_| format!("type {kind} is out of range"))?,
+            position: Vec3::new(float(2, "x")?, float(3, "y")?, float(4, "z")?),
+            radius: float(5, "radius")?,
+            parent: integer(6, "parent")?,
+        })
+    }
+
+    fn point(&self) -> SkeletonPoint {
+        SkeletonPoint {
+            position: self.position,
+            rotation: Quat::IDENTITY,
+            radius: self.radius,
+            color: Vec4::ONE,
+            material_id: self.material_id,
+            uv_scale: 1.0,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Tropism terms that bend the turtle's heading after every Draw.
+//!
+//! The per-branch directional tropism lives on [`TurtleState`](crate::TurtleState)
+//! and can be changed from the grammar; the terms here are configured globally in
+//! [`TurtleConfig::tropisms`](crate::TurtleConfig::tropisms) and apply to every branch.
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// A user-supplied vector field sampled at the turtle's position.
+///
+/// The returned vector's direction is the bending target and its magnitude scales
+/// the bending strength, as with the ABOP tropism vector `T`.
+pub trait TropismField: Send + Sync {
+    fn sample(&self, position: Vec3) -> Vec3;
+}
+
+/// A single tropism term.
+///
+/// Each term bends the heading `H` about `H × T` by `elasticity * |H × T|`.
+/// Negative elasticity bends away from the target instead.
+///
+/// [`Tropism::Field`] cannot be serialized; serializing a config that uses it fails.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Tropism {
+    /// Bend toward a fixed direction (gravity, prevailing wind).
+    Directional { direction: Vec3, elasticity: f32 },
+    /// Bend toward a point, such as a light source. Use negative elasticity to repel.
+    Point { position: Vec3, elasticity: f32 },
+    /// Bend along a user-supplied vector field.
+    #[serde(skip)]
+    Field {
+        field: Arc<dyn TropismField>,
+        elasticity: f32,
+    },
+}
+
+impl Tropism {
+    /// Returns the target vector and elasticity of this term at `position`.
+    pub fn sample(&self, position: Vec3) -> (Vec3, f32) {
+        match self {
+            Tropism::Directional {
+                direction,
+                elasticity,
+            } => (*direction, *elasticity),
+            Tropism::Point {
+                position: target,
+                elasticity,
+            } => ((*target - position).normalize_or_zero(), *elasticity),
+            Tropism::Field { field, elasticity } => (field.sample(position), *elasticity),
+        }
+    }
+}
+
+impl fmt::Debug for Tropism {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tropism::Directional {
+                direction,
+                elasticity,
+            } => f
+                .debug_struct("Directional")
+                .field("direction", direction)
+                .field("elasticity", elasticity)
+                .finish(),
+            Tropism::Point {
+                position,
+                elasticity,
+            } => f
+                .debug_struct("Point")
+                .field("position", position)
+                .field("elasticity", elasticity)
+                .finish(),
+            Tropism::Field { elasticity, .. } => f
+                .debug_struct("Field")
+                .field("elasticity", elasticity)
+                .finish_non_exhaustive(),
+        }
+    }
+}
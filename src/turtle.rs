@@ -1,5 +1,7 @@
 //! Turtle state and operations for 3D L-System interpretation.
 
+use crate::query::QueryKind;
+use crate::skeleton::SkeletonPoint;
 use glam::{Quat, Vec3, Vec4};
 use serde::{Deserialize, Serialize};
 
@@ -24,6 +26,12 @@ pub struct TurtleState {
     pub material_id: u8,
     /// UV texture coordinate scale factor.
     pub uv_scale: f32,
+    /// Directional tropism vector for the current branch, or `None` for no bending.
+    #[serde(default)]
+    pub tropism: Option<Vec3>,
+    /// How strongly the current branch bends toward `tropism`.
+    #[serde(default)]
+    pub elasticity: f32,
 }
 
 impl Default for TurtleState {
@@ -35,6 +43,8 @@ impl Default for TurtleState {
             color: Vec4::ONE, // White, opaque
             material_id: 0,
             uv_scale: 1.0,
+            tropism: None,
+            elasticity: 0.0,
         }
     }
 }
@@ -79,6 +89,29 @@ impl TurtleState {
         self.rotation = rot * self.rotation;
     }
 
+    /// Bends the heading toward `target` by `elasticity * |H × T|` (ABOP tropism).
+    ///
+    /// Does nothing when the heading is (anti)parallel to the target.
+    pub fn bend_toward(&mut self, target: Vec3, elasticity: f32) {
+        let h_cross_t = self.up().cross(target);
+        let mag = h_cross_t.length();
+        if mag > 0.0001 {
+            self.rotate_axis(h_cross_t / mag, elasticity * mag);
+        }
+    }
+
+    /// Captures the turtle's position, orientation, width and material as a skeleton point.
+    pub fn to_point(&self) -> SkeletonPoint {
+        SkeletonPoint {
+            position: self.position,
+            rotation: self.rotation,
+            radius: self.width / 2.0,
+            color: self.color,
+            material_id: self.material_id,
+            uv_scale: self.uv_scale,
+        }
+    }
+
     /// Aligns the turtle's up vector to the target direction, minimizing twist.
     ///
     /// Uses a robust fallback when vectors are nearly 180° opposed to prevent
@@ -101,7 +134,7 @@ impl TurtleState {
 /// Operations that can be performed by the turtle during L-System interpretation.
 ///
 /// Each variant corresponds to a standard L-System symbol or PBR extension.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TurtleOp {
     /// Draw forward, adding a segment to the skeleton (`F`).
     Draw,
@@ -123,7 +156,14 @@ pub enum TurtleOp {
     Push,
     /// Pop state from stack (`]`).
     Pop,
+    /// Discard the rest of the current branch up to its closing `]` (`%`).
+    ///
+    /// Outside any branch, discards the rest of the string.
+    Cut,
     /// Spawn a prop at current position (`~`). Contains default prop ID.
+    ///
+    /// Props whose scale is 0, including those not yet grown under
+    /// [`GrowthConfig::prop_scale`](crate::GrowthConfig::prop_scale), are not spawned.
     Spawn(u16),
     /// Set color - accepts 1 (grayscale), 3 (RGB), or 4 (RGBA) params (`'`).
     SetColor,
@@ -131,6 +171,25 @@ pub enum TurtleOp {
     SetMaterial,
     /// Set UV texture coordinate scale (`;`).
     SetUVScale,
+    /// Set the branch's tropism vector from 3 params `(x, y, z)`; no params disables it.
+    SetTropism,
+    /// Set the branch's tropism elasticity.
+    SetElasticity,
+    /// Start a new polygon, suspending any polygon already in progress (`{`).
+    BeginPolygon,
+    /// Finish the current polygon and resume the enclosing one (`}`).
+    EndPolygon,
+    /// Record the current position as a vertex of the current polygon (`.`).
+    RecordVertex,
+    /// Run the handler registered under this ID with
+    /// [`TurtleInterpreter::register_custom_op`](crate::TurtleInterpreter::register_custom_op).
+    ///
+    /// Ignored when no handler is registered.
+    Custom(u16),
+    /// Capture turtle state into the module's parameters (`?P`, `?H`, `?U`).
+    ///
+    /// See [`TurtleInterpreter::build_skeleton_with_queries`](crate::TurtleInterpreter::build_skeleton_with_queries).
+    Query(QueryKind),
     /// Ignored symbol (no operation).
     Ignore,
 }
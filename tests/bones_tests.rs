@@ -0,0 +1,124 @@
+use approx::assert_relative_eq;
+use glam::Vec3;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{BoneHierarchy, Skeleton, TurtleConfig, TurtleInterpreter};
+
+fn build(sequence: &str) -> Skeleton {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+
+    for sym in ["F", "+", "/", "[", "]"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let id = interner.resolve_id(token).unwrap();
+        state.push(id, 0.0, &[]).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+#[test]
+fn test_joints_every_interval_points() {
+    let skeleton = build("F F F F F");
+    let bones = BoneHierarchy::from_skeleton(&skeleton, 2);
+
+    let placed: Vec<_> = bones.joints.iter().map(|j| (j.point, j.parent)).collect();
+    assert_eq!(placed, vec![(0, None), (2, Some(0)), (4, Some(1))]);
+    assert_eq!(bones.joint_at(0, 3), Some(1));
+    assert_eq!(bones.joint_at(0, 5), Some(2));
+    assert_eq!(bones.joint_at(1, 0), None);
+}
+
+#[test]
+fn test_branches_attach_to_governing_parent_joint() {
+    // Trunk [p0 p1 p2], branch from p2, trunk resumed from p2
+    let skeleton = build("F F [ + F F ] F");
+    let bones = BoneHierarchy::from_skeleton(&skeleton, 2);
+
+    let placed: Vec<_> = bones
+        .joints
+        .iter()
+        .map(|j| (j.strand, j.point, j.parent))
+        .collect();
+    assert_eq!(
+        placed,
+        vec![
+            (0, 0, None),
+            (0, 2, Some(0)),
+            (1, 0, Some(1)),
+            (1, 2, Some(2)),
+            (2, 0, Some(1)),
+        ]
+    );
+}
+
+#[test]
+fn test_influences_split_by_arc_length() {
+    let skeleton = build("F F F F F");
+    let bones = BoneHierarchy::from_skeleton(&skeleton, 4);
+
+    let [(a, wa), (b, wb)] = bones.influences(&skeleton, 0, 1).unwrap();
+    assert_eq!((a, b), (0, 1));
+    assert_relative_eq!(wa, 0.75, epsilon = 1e-5);
+    assert_relative_eq!(wb, 0.25, epsilon = 1e-5);
+
+    // Past the last joint, the point follows it alone
+    let [(a, wa), (_, wb)] = bones.influences(&skeleton, 0, 5).unwrap();
+    assert_eq!((a, wa, wb), (1, 1.0, 0.0));
+}
+
+#[test]
+fn test_local_transforms_compose_to_rest_pose() {
+    let skeleton = build("F / F [ + F F ] F + F");
+    let bones = BoneHierarchy::from_skeleton(&skeleton, 1);
+
+    for (index, joint) in bones.joints.iter().enumerate() {
+        // Walk up to the root, composing local transforms
+        let (mut position, mut rotation) = bones.local_transform(index);
+        let mut parent = joint.parent;
+        while let Some(p) = parent {
+            let (t, r) = bones.local_transform(p);
+            position = t + r * position;
+            rotation = r * rotation;
+            parent = bones.joints[p].parent;
+        }
+        assert!(position.distance(joint.position) < 1e-4);
+        assert!(rotation.dot(joint.rotation).abs() > 0.9999);
+    }
+
+    // Steps along the heading are local +Y translations
+    let (translation, _) = bones.local_transform(1);
+    assert!(translation.distance(Vec3::Y) < 1e-5);
+}
+
+#[test]
+fn test_single_point_strands_get_no_joints() {
+    // s1 and s3 are the outer branch before and after its sub-branch, s4 the resumed
+    // trunk: single points without geometry
+    let skeleton = build("F [ [ + F ] ]");
+    assert_eq!(skeleton.strands.len(), 5);
+    let bones = BoneHierarchy::from_skeleton(&skeleton, 1);
+
+    let placed: Vec<_> = bones
+        .joints
+        .iter()
+        .map(|j| (j.strand, j.point, j.parent))
+        .collect();
+    // The sub-branch hangs off the trunk joint its empty parent grew from
+    assert_eq!(
+        placed,
+        vec![
+            (0, 0, None),
+            (0, 1, Some(0)),
+            (2, 0, Some(1)),
+            (2, 1, Some(2))
+        ]
+    );
+    for strand in [1, 3, 4] {
+        assert_eq!(bones.joint_at(strand, 0), None);
+        assert_eq!(bones.influences(&skeleton, strand, 0), None);
+    }
+}
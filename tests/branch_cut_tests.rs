@@ -0,0 +1,109 @@
+use approx::assert_relative_eq;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{Skeleton, TurtleConfig, TurtleInterpreter};
+
+fn setup(config: TurtleConfig) -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(config);
+
+    for sym in ["F", "+", "[", "]", "%", "~"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    (interpreter, interner)
+}
+
+fn run(sequence: &str, interpreter: &TurtleInterpreter, interner: &SymbolTable) -> Skeleton {
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let id = interner.resolve_id(token).unwrap();
+        state.push(id, 0.0, &[]).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+fn positions(skeleton: &Skeleton) -> Vec<Vec<[f32; 3]>> {
+    skeleton
+        .strands
+        .iter()
+        .map(|s| s.iter().map(|p| p.position.to_array()).collect())
+        .collect()
+}
+
+#[test]
+fn test_over_deep_push_skips_subtree() {
+    let (interpreter, interner) = setup(TurtleConfig {
+        max_stack_depth: 1,
+        ..Default::default()
+    });
+
+    let limited = run("F [ + F [ + F F ~ ] F ] F", &interpreter, &interner);
+    let pruned = run("F [ + F F ] F", &interpreter, &interner);
+
+    assert_eq!(positions(&limited), positions(&pruned));
+    assert!(
+        limited.props.is_empty(),
+        "Props inside the subtree are skipped"
+    );
+
+    // The trunk resumes from its own branch point, not an unrelated ancestor
+    let trunk_end = limited.strands.last().unwrap().last().unwrap().position;
+    assert_relative_eq!(trunk_end.y, 2.0, epsilon = 1e-5);
+    assert_relative_eq!(trunk_end.x, 0.0, epsilon = 1e-5);
+}
+
+#[test]
+fn test_zero_depth_limit_skips_every_branch() {
+    let (interpreter, interner) = setup(TurtleConfig {
+        max_stack_depth: 0,
+        ..Default::default()
+    });
+
+    let skeleton = run("F [ + F ] [ F [ F ] ] F", &interpreter, &interner);
+    assert_eq!(skeleton.strands.len(), 1);
+    assert_eq!(skeleton.strands[0].len(), 3);
+}
+
+#[test]
+fn test_unclosed_over_deep_push_skips_to_end() {
+    let (interpreter, interner) = setup(TurtleConfig {
+        max_stack_depth: 0,
+        ..Default::default()
+    });
+
+    let skeleton = run("F [ F F", &interpreter, &interner);
+    assert_eq!(skeleton.strands[0].len(), 2);
+}
+
+#[test]
+fn test_cut_discards_rest_of_branch() {
+    let (interpreter, interner) = setup(TurtleConfig::default());
+
+    let cut = run("F [ + F % F [ F ] ~ ] F", &interpreter, &interner);
+    let pruned = run("F [ + F ] F", &interpreter, &interner);
+
+    assert_eq!(positions(&cut), positions(&pruned));
+    assert!(cut.props.is_empty());
+}
+
+#[test]
+fn test_cut_outside_branch_discards_rest_of_string() {
+    let (interpreter, interner) = setup(TurtleConfig::default());
+
+    let skeleton = run("F % F F ~", &interpreter, &interner);
+    assert_eq!(skeleton.strands.len(), 1);
+    assert_eq!(skeleton.strands[0].len(), 2);
+    assert!(skeleton.props.is_empty());
+}
+
+#[test]
+fn test_cut_outside_branch_ignores_stray_pop() {
+    let (interpreter, interner) = setup(TurtleConfig::default());
+
+    let skeleton = run("F % F ] F F", &interpreter, &interner);
+    assert_eq!(
+        positions(&skeleton),
+        positions(&run("F", &interpreter, &interner))
+    );
+}
@@ -0,0 +1,214 @@
+use approx::assert_relative_eq;
+use glam::{Quat, Vec3};
+use std::f32::consts::FRAC_PI_2;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{
+    Axis, Conventions, Handedness, MeshConfig, ObjConfig, ObjGeometry, QueryKind, Skeleton,
+    SkeletonMesh, TurtleConfig, TurtleInterpreter, write_obj,
+};
+
+fn setup(config: TurtleConfig) -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(config);
+
+    for sym in ["F", "+", "&", "\\", "[", "]", "$", "~", "?P"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    (interpreter, interner)
+}
+
+fn parse(sequence: &str, interner: &SymbolTable) -> SymbiosState {
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let (sym, params) = match token.find('(') {
+            Some(start) => (
+                &token[..start],
+                token[start + 1..token.len() - 1]
+                    .split(',')
+                    .map(|v| v.parse().unwrap())
+                    .collect(),
+            ),
+            None => (token, Vec::new()),
+        };
+        let id = interner.resolve_id(sym).unwrap();
+        state.push(id, 0.0, &params).unwrap();
+    }
+    state
+}
+
+fn run(sequence: &str, config: TurtleConfig) -> Skeleton {
+    let (interpreter, interner) = setup(config);
+    interpreter.build_skeleton(&parse(sequence, &interner))
+}
+
+fn z_up() -> TurtleConfig {
+    TurtleConfig {
+        conventions: Conventions::Z_UP,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_z_up_grows_along_z_with_z_heading() {
+    let skeleton = run("F F", z_up());
+
+    let tip = skeleton.strands[0].last().unwrap();
+    assert_relative_eq!(tip.position.z, 2.0, epsilon = 1e-5);
+    assert_relative_eq!(tip.position.y, 0.0, epsilon = 1e-5);
+
+    let heading = tip.rotation * Vec3::Z;
+    assert_relative_eq!(heading.z, 1.0, epsilon = 1e-5);
+}
+
+#[test]
+fn test_z_up_is_a_rotation_of_y_up() {
+    let sequence = "F [ + F & F ~ ] \\(30) & F [ & F ] F";
+    let y_up = run(sequence, TurtleConfig::default());
+    let z_up = run(sequence, z_up());
+    let world = Quat::from_rotation_x(FRAC_PI_2);
+
+    assert_eq!(y_up.strands.len(), z_up.strands.len());
+    for (a, b) in y_up
+        .strands
+        .iter()
+        .flatten()
+        .zip(z_up.strands.iter().flatten())
+    {
+        let expected = world * a.position;
+        assert!(
+            expected.abs_diff_eq(b.position, 1e-4),
+            "{expected} != {}",
+            b.position
+        );
+        // Heading axis follows the convention
+        let heading_y = world * (a.rotation * Vec3::Y);
+        let heading_z = b.rotation * Vec3::Z;
+        assert!(heading_y.abs_diff_eq(heading_z, 1e-4));
+    }
+    assert!(
+        (world * y_up.props[0].position).abs_diff_eq(z_up.props[0].position, 1e-4),
+        "Props are converted too"
+    );
+}
+
+#[test]
+fn test_vertical_uses_configured_world_up() {
+    let skeleton = run("\\(30) &(40) $ F", z_up());
+
+    // After `$` the turtle's left axis (local X) is horizontal in the Z-up world
+    let rotation = skeleton.strands[0].last().unwrap().rotation;
+    assert_relative_eq!((rotation * Vec3::X).z, 0.0, epsilon = 1e-4);
+}
+
+#[test]
+fn test_tropism_is_given_in_configured_world() {
+    let config = TurtleConfig {
+        tropism: Some(Vec3::new(0.0, 0.0, -1.0)),
+        elasticity: 0.3,
+        ..z_up()
+    };
+    let skeleton = run("&(90) F F F F", config);
+
+    let tip = skeleton.strands[0].last().unwrap().position;
+    assert!(tip.z < -0.5, "Branch should droop toward -Z, got {tip}");
+}
+
+#[test]
+fn test_left_handed_flips_rotation_sense() {
+    let right = run("+(90) F", TurtleConfig::default());
+    let left = run(
+        "+(90) F",
+        TurtleConfig {
+            conventions: Conventions {
+                handedness: Handedness::Left,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let r = right.strands[0].last().unwrap().position;
+    let l = left.strands[0].last().unwrap().position;
+    assert_relative_eq!(r.x, -l.x, epsilon = 1e-5);
+    assert!(r.x.abs() > 0.9);
+}
+
+#[test]
+fn test_query_values_use_configured_world() {
+    let (interpreter, interner) = setup(z_up());
+    let state = parse("F(2) ?P(0,0,0)", &interner);
+    let (_, patches) = interpreter.build_skeleton_with_queries(&state);
+
+    assert_eq!(patches[0].kind, QueryKind::Position);
+    assert_relative_eq!(patches[0].values[2], 2.0, epsilon = 1e-5);
+}
+
+#[test]
+fn test_rotation_minimizing_frames_respect_heading_axis() {
+    let config = TurtleConfig {
+        conventions: Conventions {
+            heading: Axis::Z,
+            ..Default::default()
+        },
+        rotation_minimizing_frames: true,
+        ..Default::default()
+    };
+    let skeleton = run("F &(30) F \\(50) &(30) F", config);
+
+    let strand = &skeleton.strands[0];
+    for w in strand.windows(2) {
+        let tangent = (w[1].position - w[0].position).normalize();
+        let heading = w[1].rotation * Vec3::Z;
+        assert!(
+            heading.dot(tangent) > 0.8,
+            "Heading should follow the strand"
+        );
+    }
+}
+
+#[test]
+fn test_exports_follow_configured_heading() {
+    let skeleton = run(
+        "F ~",
+        TurtleConfig {
+            conventions: Conventions::Z_UP,
+            ..Default::default()
+        },
+    );
+    assert_eq!(skeleton.heading, Axis::Z);
+
+    // The OBJ prop placeholder points along the heading, i.e. world up
+    let config = ObjConfig {
+        geometry: ObjGeometry::Polylines,
+        ..Default::default()
+    };
+    let (mut obj, mut mtl) = (Vec::new(), Vec::new());
+    write_obj(&skeleton, &config, "tree.mtl", &mut obj, &mut mtl).unwrap();
+    let vertices: Vec<Vec3> = String::from_utf8(obj)
+        .unwrap()
+        .lines()
+        .filter_map(|l| l.strip_prefix("v "))
+        .map(|l| {
+            let c: Vec<f32> = l.split(' ').map(|v| v.parse().unwrap()).collect();
+            Vec3::new(c[0], c[1], c[2])
+        })
+        .collect();
+    let line = vertices[vertices.len() - 1] - vertices[vertices.len() - 2];
+    let prop = &skeleton.props[0];
+    assert!(line.abs_diff_eq(Vec3::Z * prop.scale.z, 1e-5));
+
+    // A strand without length falls back to the heading, so its rings lie across world up
+    let point = skeleton.strands[0][1];
+    let degenerate = Skeleton {
+        strands: vec![vec![point, point]],
+        heading: Axis::Z,
+        ..Default::default()
+    };
+    let mesh = SkeletonMesh::build(&degenerate, &MeshConfig::default());
+    assert!(!mesh.positions.is_empty());
+    for position in &mesh.positions {
+        assert_relative_eq!(position.z, point.position.z, epsilon = 1e-5);
+    }
+}
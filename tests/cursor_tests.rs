@@ -0,0 +1,102 @@
+use std::time::Duration;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{Skeleton, TurtleConfig, TurtleInterpreter};
+
+fn setup() -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+
+    for sym in ["F", "+", "&", "[", "]", "~"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    (interpreter, interner)
+}
+
+fn parse(sequence: &str, interner: &SymbolTable) -> SymbiosState {
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let id = interner.resolve_id(token).unwrap();
+        state.push(id, 0.0, &[]).unwrap();
+    }
+    state
+}
+
+fn tree(interner: &SymbolTable) -> SymbiosState {
+    parse(&"F [ + F ~ ] [ & F [ + F ] ] F ".repeat(50), interner)
+}
+
+fn assert_same(a: &Skeleton, b: &Skeleton) {
+    assert_eq!(a.strands.len(), b.strands.len());
+    assert_eq!(a.strand_info, b.strand_info);
+    assert_eq!(a.props.len(), b.props.len());
+    for (sa, sb) in a.strands.iter().zip(&b.strands) {
+        assert_eq!(sa.len(), sb.len());
+        for (pa, pb) in sa.iter().zip(sb) {
+            assert_eq!(pa.position, pb.position);
+            assert_eq!(pa.rotation, pb.rotation);
+        }
+    }
+}
+
+#[test]
+fn test_chunked_matches_full_build() {
+    let (interpreter, interner) = setup();
+    let state = tree(&interner);
+    let full = interpreter.build_skeleton(&state);
+
+    for chunk in [1, 7, 64] {
+        let mut cursor = interpreter.cursor(&state);
+        while !cursor.advance(chunk) {}
+        assert_same(&cursor.finish(), &full);
+    }
+}
+
+#[test]
+fn test_partial_results_grow_between_chunks() {
+    let (interpreter, interner) = setup();
+    let state = tree(&interner);
+    let mut cursor = interpreter.cursor(&state);
+
+    assert!(!cursor.advance(10));
+    assert_eq!(cursor.position(), 10);
+    let partial_points: usize = cursor.skeleton().strands.iter().map(Vec::len).sum();
+    assert!(partial_points > 0);
+    assert!(cursor.progress() > 0.0 && cursor.progress() < 1.0);
+
+    assert!(!cursor.advance(100));
+    let more_points: usize = cursor.skeleton().strands.iter().map(Vec::len).sum();
+    assert!(more_points > partial_points);
+
+    assert!(cursor.advance(usize::MAX));
+    assert!(cursor.is_finished());
+    assert_eq!(cursor.progress(), 1.0);
+    assert_eq!(cursor.position(), state.len());
+}
+
+#[test]
+fn test_time_budget_eventually_finishes() {
+    let (interpreter, interner) = setup();
+    let state = tree(&interner);
+    let full = interpreter.build_skeleton(&state);
+
+    let mut cursor = interpreter.cursor(&state);
+    let mut calls = 0;
+    while !cursor.advance_for(Duration::from_micros(50)) {
+        calls += 1;
+        assert!(calls < 100_000, "Cursor failed to make progress");
+    }
+    assert_same(&cursor.finish(), &full);
+}
+
+#[test]
+fn test_empty_state_is_finished() {
+    let (interpreter, _) = setup();
+    let state = SymbiosState::new();
+    let cursor = interpreter.cursor(&state);
+
+    assert!(cursor.is_finished());
+    assert_eq!(cursor.progress(), 1.0);
+    assert!(cursor.finish().strands.is_empty());
+}
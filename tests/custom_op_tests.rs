@@ -0,0 +1,117 @@
+use approx::assert_relative_eq;
+use glam::Vec3;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{Skeleton, SkeletonProp, TurtleConfig, TurtleInterpreter, TurtleOp};
+
+const RUNG: u16 = 0;
+const THICKEN: u16 = 1;
+const CLUSTER: u16 = 2;
+
+fn setup() -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+
+    for sym in ["F", "[", "]", "R", "W", "C", "U"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    interpreter.set_op(interner.resolve_id("R").unwrap(), TurtleOp::Custom(RUNG));
+    interpreter.set_op(interner.resolve_id("W").unwrap(), TurtleOp::Custom(THICKEN));
+    interpreter.set_op(interner.resolve_id("C").unwrap(), TurtleOp::Custom(CLUSTER));
+    interpreter.set_op(interner.resolve_id("U").unwrap(), TurtleOp::Custom(99));
+
+    // Ladder rung: a sideways strand of the given length; the turtle stays put
+    interpreter.register_custom_op(RUNG, |turtle, params, sink| {
+        let length = params.first().copied().unwrap_or(1.0) as f32;
+        let mut end = turtle.to_point();
+        end.position += turtle.right() * length;
+        sink.add_point(turtle.to_point());
+        sink.add_point(end);
+    });
+    interpreter.register_custom_op(THICKEN, |turtle, params, _| {
+        turtle.width *= params.first().copied().unwrap_or(2.0) as f32;
+    });
+    // Leaf cluster: three props around the turtle inheriting its material
+    interpreter.register_custom_op(CLUSTER, |turtle, _, sink| {
+        for k in 0..3 {
+            sink.spawn_prop(SkeletonProp {
+                prop_id: 7,
+                position: turtle.position + Vec3::Y * k as f32 * 0.1,
+                rotation: turtle.rotation,
+                scale: Vec3::ONE,
+                color: turtle.color,
+                material_id: turtle.material_id,
+            });
+        }
+    });
+    (interpreter, interner)
+}
+
+fn run(sequence: &str, interpreter: &TurtleInterpreter, interner: &SymbolTable) -> Skeleton {
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let (sym, params) = match token.find('(') {
+            Some(start) => (
+                &token[..start],
+                vec![token[start + 1..token.len() - 1].parse().unwrap()],
+            ),
+            None => (token, vec![]),
+        };
+        let id = interner.resolve_id(sym).unwrap();
+        state.push(id, 0.0, &params).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+#[test]
+fn test_handler_emits_geometry_through_sink() {
+    let (interpreter, interner) = setup();
+    let skeleton = run("F [ R(0.5) ] F", &interpreter, &interner);
+
+    // Trunk, rung branch, resumed trunk
+    assert_eq!(skeleton.strands.len(), 3);
+    let rung = &skeleton.strands[1];
+    assert_eq!(rung.len(), 2, "Duplicate start point should be dropped");
+    assert_relative_eq!(rung[1].position.x, 0.5, epsilon = 1e-5);
+    assert_eq!(skeleton.strand_info[1].parent, Some(0));
+}
+
+#[test]
+fn test_points_from_handler_update_attachment() {
+    let (interpreter, interner) = setup();
+    let skeleton = run("F R(1) [ F ]", &interpreter, &interner);
+
+    // The branch attaches to the rung's end point, which the handler appended
+    let info = skeleton.strand_info[1];
+    assert_eq!(info.parent, Some(0));
+    assert_eq!(info.parent_point, skeleton.strands[0].len() - 1);
+}
+
+#[test]
+fn test_turtle_changes_persist_and_are_scoped_by_stack() {
+    let (interpreter, interner) = setup();
+    let skeleton = run("F [ W(3) F ] F", &interpreter, &interner);
+
+    let default_radius = TurtleConfig::default().initial_width / 2.0;
+    assert_relative_eq!(skeleton.strands[1][1].radius, default_radius * 3.0);
+    assert_relative_eq!(skeleton.strands[2][1].radius, default_radius);
+}
+
+#[test]
+fn test_handler_spawns_props() {
+    let (interpreter, interner) = setup();
+    let skeleton = run("F C", &interpreter, &interner);
+
+    assert_eq!(skeleton.props.len(), 3);
+    assert!(skeleton.props.iter().all(|p| p.prop_id == 7));
+}
+
+#[test]
+fn test_unregistered_custom_op_is_ignored() {
+    let (interpreter, interner) = setup();
+    let skeleton = run("F U F", &interpreter, &interner);
+
+    assert_eq!(skeleton.strands.len(), 1);
+    assert_eq!(skeleton.strands[0].len(), 3);
+}
@@ -0,0 +1,169 @@
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{
+    Diagnostic, DiagnosticKind, InterpretError, TurtleConfig, TurtleInterpreter, TurtleOp,
+};
+
+fn setup(config: TurtleConfig) -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(config);
+
+    for sym in ["F", "+", "!", ",", "~", "[", "]", "A", "B"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    (interpreter, interner)
+}
+
+fn parse(sequence: &[(&str, &[f64])], interner: &SymbolTable) -> SymbiosState {
+    let mut state = SymbiosState::new();
+    for (token, params) in sequence {
+        let id = interner.resolve_id(token).unwrap();
+        state.push(id, 0.0, params).unwrap();
+    }
+    state
+}
+
+fn kinds(config: TurtleConfig, sequence: &[(&str, &[f64])]) -> Vec<(usize, DiagnosticKind)> {
+    let (interpreter, interner) = setup(config);
+    let state = parse(sequence, &interner);
+    let (_, report) = interpreter.try_build_skeleton(&state).unwrap();
+    report
+        .diagnostics
+        .iter()
+        .map(|d| (d.module_index, d.kind))
+        .collect()
+}
+
+#[test]
+fn test_clean_string_has_empty_report() {
+    let (interpreter, interner) = setup(TurtleConfig::default());
+    let state = parse(
+        &[
+            ("F", &[]),
+            ("[", &[]),
+            ("+", &[30.0]),
+            ("F", &[]),
+            ("]", &[]),
+        ],
+        &interner,
+    );
+
+    let (skeleton, report) = interpreter.try_build_skeleton(&state).unwrap();
+    assert!(report.is_empty());
+    let expected = interpreter.build_skeleton(&state);
+    assert_eq!(skeleton.strands.len(), expected.strands.len());
+    assert_eq!(skeleton.strand_info, expected.strand_info);
+}
+
+#[test]
+fn test_unbalanced_brackets() {
+    let found = kinds(
+        TurtleConfig::default(),
+        &[("F", &[]), ("]", &[]), ("[", &[]), ("F", &[]), ("[", &[])],
+    );
+    assert_eq!(
+        found,
+        vec![
+            (1, DiagnosticKind::UnmatchedPop),
+            (2, DiagnosticKind::UnclosedPush),
+            (4, DiagnosticKind::UnclosedPush),
+        ]
+    );
+}
+
+#[test]
+fn test_parameter_problems() {
+    let found = kinds(
+        TurtleConfig::default(),
+        &[
+            ("+", &[1e300]),
+            ("!", &[-0.5]),
+            (",", &[300.0]),
+            (",", &[2.5]),
+            (",", &[7.0]),
+            ("~", &[-1.0]),
+        ],
+    );
+    assert_eq!(
+        found,
+        vec![
+            (0, DiagnosticKind::NonFiniteParameter { param: 0 }),
+            (1, DiagnosticKind::NegativeWidth { width: -0.5 }),
+            (2, DiagnosticKind::MaterialOutOfRange { value: 300.0 }),
+            (3, DiagnosticKind::MaterialOutOfRange { value: 2.5 }),
+            (5, DiagnosticKind::PropIdOutOfRange { value: -1.0 }),
+        ]
+    );
+}
+
+#[test]
+fn test_stack_depth_truncation() {
+    let found = kinds(
+        TurtleConfig {
+            max_stack_depth: 1,
+            ..Default::default()
+        },
+        &[("[", &[]), ("[", &[]), ("F", &[]), ("]", &[]), ("]", &[])],
+    );
+    assert_eq!(found, vec![(1, DiagnosticKind::StackDepthExceeded)]);
+}
+
+#[test]
+fn test_unmapped_symbols_are_counted() {
+    let (mut interpreter, interner) = setup(TurtleConfig::default());
+    let state = parse(
+        &[("A", &[]), ("F", &[]), ("B", &[]), ("A", &[]), ("A", &[])],
+        &interner,
+    );
+
+    let (_, report) = interpreter.try_build_skeleton(&state).unwrap();
+    let unmapped: Vec<_> = report
+        .unmapped_symbols
+        .iter()
+        .map(|u| (u.symbol, u.first_index, u.count))
+        .collect();
+    let a = interner.resolve_id("A").unwrap();
+    let b = interner.resolve_id("B").unwrap();
+    assert_eq!(unmapped, vec![(a, 0, 3), (b, 2, 1)]);
+
+    // Explicit Ignore mappings are not reported
+    interpreter.set_op(a, TurtleOp::Ignore);
+    interpreter.set_op(b, TurtleOp::Ignore);
+    let (_, report) = interpreter.try_build_skeleton(&state).unwrap();
+    assert!(report.is_empty());
+}
+
+#[test]
+fn test_strict_mode_returns_first_problem() {
+    let (interpreter, interner) = setup(TurtleConfig {
+        strict: true,
+        ..Default::default()
+    });
+
+    let state = parse(&[("F", &[]), ("A", &[]), ("]", &[])], &interner);
+    let err = interpreter.try_build_skeleton(&state).unwrap_err();
+    assert_eq!(
+        err,
+        InterpretError::Strict(Diagnostic {
+            module_index: 1,
+            kind: DiagnosticKind::UnmappedSymbol {
+                symbol: interner.resolve_id("A").unwrap()
+            },
+        })
+    );
+    assert!(err.to_string().contains("module 1"));
+
+    // Unclosed branches are only known at the end of the string
+    let state = parse(&[("[", &[]), ("F", &[])], &interner);
+    assert_eq!(
+        interpreter.try_build_skeleton(&state).unwrap_err(),
+        InterpretError::Strict(Diagnostic {
+            module_index: 0,
+            kind: DiagnosticKind::UnclosedPush,
+        })
+    );
+
+    // Infallible builds ignore strict mode
+    assert_eq!(interpreter.build_skeleton(&state).strands.len(), 1);
+}
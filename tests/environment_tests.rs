@@ -0,0 +1,214 @@
+use approx::assert_relative_eq;
+use glam::Vec3;
+use std::sync::Arc;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{Environment, ObstaclePolicy, Skeleton, TurtleConfig, TurtleInterpreter};
+
+/// Solid half-space above `y = height`.
+struct Ceiling {
+    height: f32,
+}
+
+impl Environment for Ceiling {
+    fn signed_distance(&self, point: Vec3) -> f32 {
+        self.height - point.y
+    }
+}
+
+/// Solid sphere.
+struct Ball {
+    center: Vec3,
+    radius: f32,
+}
+
+impl Environment for Ball {
+    fn signed_distance(&self, point: Vec3) -> f32 {
+        point.distance(self.center) - self.radius
+    }
+}
+
+fn setup(
+    policy: ObstaclePolicy,
+    env: Option<Arc<dyn Environment>>,
+) -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig {
+        obstacle_policy: policy,
+        ..Default::default()
+    });
+    if let Some(env) = env {
+        interpreter = interpreter.with_environment(env);
+    }
+
+    for sym in ["F", "f", "+", "&", "[", "]"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    (interpreter, interner)
+}
+
+fn run(sequence: &str, interpreter: &TurtleInterpreter, interner: &SymbolTable) -> Skeleton {
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let (sym, params) = match token.find('(') {
+            Some(start) => (
+                &token[..start],
+                vec![token[start + 1..token.len() - 1].parse().unwrap()],
+            ),
+            None => (token, vec![]),
+        };
+        let id = interner.resolve_id(sym).unwrap();
+        state.push(id, 0.0, &params).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+fn end_of(skeleton: &Skeleton, strand: usize) -> Vec3 {
+    skeleton.strands[strand].last().unwrap().position
+}
+
+#[test]
+fn test_without_environment_growth_is_unconstrained() {
+    let (interpreter, interner) = setup(ObstaclePolicy::Stop, None);
+    let skeleton = run("F(1) F(1) F(1)", &interpreter, &interner);
+
+    assert_relative_eq!(end_of(&skeleton, 0).y, 3.0, epsilon = 1e-5);
+    assert!(skeleton.terminated.is_empty());
+}
+
+#[test]
+fn test_stop_clips_segment_at_surface() {
+    let env = Arc::new(Ceiling { height: 1.5 });
+    let (interpreter, interner) = setup(ObstaclePolicy::Stop, Some(env));
+    let skeleton = run("F(1) F(1) F(1)", &interpreter, &interner);
+
+    assert_eq!(skeleton.strands[0].len(), 3, "Third Draw should be ignored");
+    assert_relative_eq!(end_of(&skeleton, 0).y, 1.5, epsilon = 1e-3);
+    assert!(skeleton.terminated.is_empty());
+}
+
+#[test]
+fn test_stopped_branch_resumes_parent_after_pop() {
+    let env = Arc::new(Ceiling { height: 1.5 });
+    let (interpreter, interner) = setup(ObstaclePolicy::Stop, Some(env));
+
+    // Branch hits the ceiling; the parent turns sideways and keeps growing
+    let skeleton = run(
+        "F(1) [ F(1) F(1) f(1) ] +(90) F(1) F(1)",
+        &interpreter,
+        &interner,
+    );
+
+    assert_relative_eq!(end_of(&skeleton, 1).y, 1.5, epsilon = 1e-3);
+    assert_eq!(
+        skeleton.strands.len(),
+        3,
+        "Move inside a stopped branch is ignored"
+    );
+
+    let trunk_end = end_of(&skeleton, 2);
+    assert_relative_eq!(trunk_end.y, 1.0, epsilon = 1e-3);
+    assert_relative_eq!(trunk_end.length(), 5.0f32.sqrt(), epsilon = 1e-3);
+}
+
+#[test]
+fn test_terminate_records_branch() {
+    let env = Arc::new(Ceiling { height: 1.5 });
+    let (interpreter, interner) = setup(ObstaclePolicy::Terminate, Some(env));
+    let skeleton = run("F(1) [ F(1) ] [ +(90) F(1) ]", &interpreter, &interner);
+
+    assert_eq!(skeleton.terminated.len(), 1);
+    let terminated = skeleton.terminated[0];
+    assert_eq!(terminated.module_index, 2);
+    assert_eq!(terminated.strand, 1);
+    assert_relative_eq!(terminated.position.y, 1.5, epsilon = 1e-3);
+    assert_eq!(
+        end_of(&skeleton, terminated.strand),
+        terminated.position,
+        "Terminated strand should end at the contact point"
+    );
+}
+
+#[test]
+fn test_deflect_slides_along_surface() {
+    let env = Arc::new(Ceiling { height: 1.0 });
+    let (interpreter, interner) = setup(ObstaclePolicy::Deflect, Some(env));
+
+    // Heading tilted 45° toward the ceiling
+    let skeleton = run("+(45) F(2)", &interpreter, &interner);
+
+    let end = end_of(&skeleton, 0);
+    assert!(
+        end.y <= 1.0 + 1e-3,
+        "Should stay below the ceiling, got {end}"
+    );
+    assert!(
+        skeleton.strands[0].len() >= 3,
+        "Slide should add a corner node"
+    );
+
+    // The full step length is spent: first along the heading, then along the surface
+    let path_length: f32 = skeleton.strands[0]
+        .windows(2)
+        .map(|w| w[0].position.distance(w[1].position))
+        .sum();
+    assert_relative_eq!(path_length, 2.0, epsilon = 1e-2);
+    assert!(skeleton.terminated.is_empty());
+}
+
+#[test]
+fn test_deflect_head_on_does_not_penetrate() {
+    let env = Arc::new(Ceiling { height: 1.5 });
+    let (interpreter, interner) = setup(ObstaclePolicy::Deflect, Some(env));
+    let skeleton = run("F(1) F(1) F(1)", &interpreter, &interner);
+
+    for point in &skeleton.strands[0] {
+        assert!(
+            point.position.y <= 1.5 + 1e-3,
+            "Penetrated at {}",
+            point.position
+        );
+    }
+}
+
+#[test]
+fn test_deflect_head_on_stops_branch() {
+    let env = Arc::new(Ceiling { height: 1.5 });
+    let (interpreter, interner) = setup(ObstaclePolicy::Deflect, Some(env));
+
+    // After the head-on contact, turning away must not resume drawing from the surface
+    let skeleton = run("F(1) F(1) +(90) F(1) F(1)", &interpreter, &interner);
+    assert_eq!(skeleton.strands.len(), 1);
+    assert_eq!(skeleton.strands[0].len(), 3);
+    let end = end_of(&skeleton, 0);
+    assert_relative_eq!(end.y, 1.5, epsilon = 1e-3);
+    assert_relative_eq!(end.x, 0.0, epsilon = 1e-5);
+
+    // The branch is stopped, not the trunk it grew from
+    let skeleton = run("F(1) [ F(1) ] +(90) F(1)", &interpreter, &interner);
+    let resumed = skeleton.strands.last().unwrap();
+    assert_eq!(resumed.len(), 2);
+    assert_relative_eq!(resumed[1].position.y, 1.0, epsilon = 1e-5);
+}
+
+#[test]
+fn test_deflect_slides_off_sphere() {
+    let env = Arc::new(Ball {
+        center: Vec3::new(0.1, 3.0, 0.0),
+        radius: 1.0,
+    });
+    let (interpreter, interner) = setup(ObstaclePolicy::Deflect, Some(env));
+    let skeleton = run("F(1) F(1) F(1) F(1) F(1) F(1)", &interpreter, &interner);
+
+    for point in &skeleton.strands[0] {
+        let d = point.position.distance(Vec3::new(0.1, 3.0, 0.0));
+        assert!(
+            d >= 1.0 - 1e-3,
+            "Point {} is inside the ball",
+            point.position
+        );
+    }
+    let end = end_of(&skeleton, 0);
+    assert!(end.x < -0.5, "Vine should be deflected sideways, got {end}");
+}
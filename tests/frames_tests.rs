@@ -0,0 +1,81 @@
+use approx::assert_relative_eq;
+use glam::Vec3;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{Skeleton, TurtleConfig, TurtleInterpreter};
+
+fn build(sequence: &str, rotation_minimizing_frames: bool) -> Skeleton {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig {
+        rotation_minimizing_frames,
+        ..Default::default()
+    });
+    for sym in ["F", "+", "&", "\\", "[", "]"] {
+        interner.intern(sym).unwrap();
+    }
+    interpreter.populate_standard_symbols(&interner);
+
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        if let Some(start) = token.find('(') {
+            let id = interner.resolve_id(&token[..start]).unwrap();
+            let val: f64 = token[start + 1..token.len() - 1].parse().unwrap();
+            state.push(id, 0.0, &[val]).unwrap();
+        } else {
+            let id = interner.resolve_id(token).unwrap();
+            state.push(id, 0.0, &[]).unwrap();
+        }
+    }
+    interpreter.build_skeleton(&state)
+}
+
+#[test]
+fn test_roll_between_draws_does_not_twist() {
+    let sequence = "F \\(60) F \\(60) F \\(60) F";
+
+    let raw = build(sequence, false);
+    let first = raw.strands[0][0].rotation;
+    let last = raw.strands[0].last().unwrap().rotation;
+    assert!(!first.abs_diff_eq(last, 1e-3), "Turtle frame should twist");
+
+    let rmf = build(sequence, true);
+    let first = rmf.strands[0][0].rotation;
+    for point in &rmf.strands[0] {
+        assert!((point.rotation * Vec3::X).abs_diff_eq(first * Vec3::X, 1e-5));
+    }
+}
+
+#[test]
+fn test_frames_follow_tangent_and_stay_orthonormal() {
+    let skeleton = build("F +(20) \\(45) F &(30) F \\(80) +(25) F &(15) F", true);
+    let strand = &skeleton.strands[0];
+
+    for (i, point) in strand.iter().enumerate() {
+        assert_relative_eq!(point.rotation.length(), 1.0, epsilon = 1e-5);
+
+        // Heading (local +Y) follows the averaged strand tangent
+        let prev = strand[i.saturating_sub(1)].position;
+        let next = strand[(i + 1).min(strand.len() - 1)].position;
+        let incoming = (point.position - prev).normalize_or_zero();
+        let outgoing = (next - point.position).normalize_or_zero();
+        let tangent = (incoming + outgoing).normalize();
+        assert!((point.rotation * Vec3::Y).abs_diff_eq(tangent, 1e-4));
+    }
+}
+
+#[test]
+fn test_planar_curve_keeps_binormal_fixed() {
+    // A yaw-only curve stays in the XY plane; its minimizing frame keeps Z fixed
+    // even when rolls are interleaved with the turns.
+    let skeleton = build("F +(30) F \\(180) +(-30) F \\(180) +(30) F", true);
+    for point in &skeleton.strands[0] {
+        assert!((point.rotation * Vec3::Z).abs_diff_eq(Vec3::Z, 1e-4));
+    }
+}
+
+#[test]
+fn test_initial_frame_comes_from_turtle() {
+    // Roll before the first draw sets the initial frame
+    let skeleton = build("\\(90) F F", true);
+    let x = skeleton.strands[0][0].rotation * Vec3::X;
+    assert!(x.abs_diff_eq(-Vec3::Z, 1e-5), "got {x}");
+}
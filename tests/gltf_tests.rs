@@ -0,0 +1,275 @@
+use approx::assert_relative_eq;
+use serde_json::Value;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{
+    BoneHierarchy, GltfConfig, MeshConfig, Skeleton, SkeletonMesh, TurtleConfig, TurtleInterpreter,
+    read_swc, write_glb,
+};
+
+fn build(sequence: &[(&str, &[f64])]) -> Skeleton {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+
+    for sym in ["F", "+", "[", "]", ",", "~", "{", "}", "."] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    let mut state = SymbiosState::new();
+    for (token, params) in sequence {
+        let id = interner.resolve_id(token).unwrap();
+        state.push(id, 0.0, params).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+/// Splits a GLB into its JSON document and binary chunk.
+fn parse_glb(glb: &[u8]) -> (Value, Vec<u8>) {
+    let word = |offset: usize| u32::from_le_bytes(glb[offset..offset + 4].try_into().unwrap());
+    assert_eq!(&glb[0..4], b"glTF");
+    assert_eq!(word(4), 2);
+    assert_eq!(word(8) as usize, glb.len());
+
+    let json_len = word(12) as usize;
+    assert_eq!(&glb[16..20], b"JSON");
+    let document = serde_json::from_slice(&glb[20..20 + json_len]).unwrap();
+
+    let bin_start = 20 + json_len;
+    if bin_start == glb.len() {
+        return (document, Vec::new());
+    }
+    let bin_len = word(bin_start) as usize;
+    assert_eq!(&glb[bin_start + 4..bin_start + 8], b"BIN\0");
+    (
+        document,
+        glb[bin_start + 8..bin_start + 8 + bin_len].to_vec(),
+    )
+}
+
+fn export(skeleton: &Skeleton, config: &GltfConfig) -> (Value, Vec<u8>) {
+    let mut glb = Vec::new();
+    write_glb(skeleton, config, &mut glb).unwrap();
+    assert_eq!(glb.len() % 4, 0);
+    parse_glb(&glb)
+}
+
+fn read_floats(document: &Value, bin: &[u8], accessor: &Value) -> Vec<f32> {
+    let accessor = &document["accessors"][accessor.as_u64().unwrap() as usize];
+    let view = &document["bufferViews"][accessor["bufferView"].as_u64().unwrap() as usize];
+    let start = view["byteOffset"].as_u64().unwrap() as usize;
+    let len = view["byteLength"].as_u64().unwrap() as usize;
+    bin[start..start + len]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+        .collect()
+}
+
+#[test]
+fn test_tube_primitives_per_material() {
+    let skeleton = build(&[("F", &[]), ("+", &[]), (",", &[2.0]), ("F", &[])]);
+    let (document, bin) = export(&skeleton, &GltfConfig::default());
+
+    let mesh = SkeletonMesh::build(&skeleton, &MeshConfig::default());
+    assert_eq!(document["asset"]["version"], "2.0");
+    assert_eq!(document["meshes"].as_array().unwrap().len(), 1);
+    let primitives = document["meshes"][0]["primitives"].as_array().unwrap();
+    assert_eq!(primitives.len(), mesh.submeshes.len());
+
+    let materials: Vec<&str> = document["materials"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(materials, vec!["material_0", "material_2"]);
+
+    let attributes = &primitives[0]["attributes"];
+    let position = &document["accessors"][attributes["POSITION"].as_u64().unwrap() as usize];
+    assert_eq!(position["count"], mesh.vertex_count());
+    assert!(position["min"].is_array() && position["max"].is_array());
+    let colors = read_floats(&document, &bin, &attributes["COLOR_0"]);
+    assert_eq!(colors.len(), mesh.vertex_count() * 4);
+    assert_eq!(
+        document["buffers"][0]["byteLength"].as_u64().unwrap() as usize,
+        bin.len()
+    );
+}
+
+#[test]
+fn test_props_are_instanced_per_prop_id() {
+    let skeleton = build(&[
+        ("F", &[]),
+        ("~", &[1.0, 2.0]),
+        ("F", &[]),
+        ("~", &[1.0]),
+        ("~", &[5.0]),
+    ]);
+    let (document, bin) = export(&skeleton, &GltfConfig::default());
+
+    assert_eq!(document["extensionsUsed"][0], "EXT_mesh_gpu_instancing");
+    let nodes = document["nodes"].as_array().unwrap();
+    let names: Vec<&str> = nodes.iter().map(|n| n["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["strands", "prop_1", "prop_5"]);
+
+    let instancing = &nodes[1]["extensions"]["EXT_mesh_gpu_instancing"]["attributes"];
+    let translations = read_floats(&document, &bin, &instancing["TRANSLATION"]);
+    assert_eq!(translations.len(), 6);
+    assert_relative_eq!(translations[1], 1.0, epsilon = 1e-5);
+    assert_relative_eq!(translations[4], 2.0, epsilon = 1e-5);
+    let scales = read_floats(&document, &bin, &instancing["SCALE"]);
+    assert_eq!(scales, vec![2.0, 2.0, 2.0, 1.0, 1.0, 1.0]);
+    let rotations = read_floats(&document, &bin, &instancing["ROTATION"]);
+    assert_eq!(rotations.len(), 8);
+}
+
+#[test]
+fn test_custom_prop_mesh_and_polygons() {
+    let skeleton = build(&[
+        ("{", &[]),
+        (".", &[]),
+        ("F", &[]),
+        (".", &[]),
+        ("+", &[90.0]),
+        ("F", &[]),
+        (".", &[]),
+        ("}", &[]),
+        ("~", &[0.0]),
+    ]);
+    let leaf = SkeletonMesh::build(&build(&[(",", &[7.0]), ("F", &[])]), &MeshConfig::default());
+    let mut config = GltfConfig::default();
+    config.prop_meshes.insert(0, leaf.clone());
+    let (document, _) = export(&skeleton, &config);
+
+    let meshes: Vec<&str> = document["meshes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(meshes, vec!["strands", "polygons", "prop_0"]);
+    let prop_material = document["meshes"][2]["primitives"][0]["material"]
+        .as_u64()
+        .unwrap() as usize;
+    assert_eq!(document["materials"][prop_material]["name"], "material_7");
+
+    let polygon_indices = document["meshes"][1]["primitives"][0]["indices"]
+        .as_u64()
+        .unwrap();
+    assert_eq!(document["accessors"][polygon_indices as usize]["count"], 3);
+}
+
+#[test]
+fn test_empty_skeleton_is_valid() {
+    let (document, bin) = export(&Skeleton::new(), &GltfConfig::default());
+    assert!(bin.is_empty());
+    assert!(document.get("buffers").is_none());
+    assert!(document.get("scenes").is_none());
+}
+
+#[test]
+fn test_skinned_strands() {
+    let skeleton = build(&[
+        ("F", &[]),
+        ("F", &[]),
+        ("[", &[]),
+        ("+", &[]),
+        ("F", &[]),
+        ("F", &[]),
+        ("]", &[]),
+        ("F", &[]),
+    ]);
+    let config = GltfConfig {
+        joint_interval: Some(2),
+        ..Default::default()
+    };
+    let (document, bin) = export(&skeleton, &config);
+
+    let bones = BoneHierarchy::from_skeleton(&skeleton, 2);
+    let skin = &document["skins"][0];
+    let joints = skin["joints"].as_array().unwrap();
+    assert_eq!(joints.len(), bones.joints.len());
+    let inverse_binds = read_floats(&document, &bin, &skin["inverseBindMatrices"]);
+    assert_eq!(inverse_binds.len(), 16 * bones.joints.len());
+
+    // Only the armature holding the root joint and the mesh nodes are listed in the scene
+    let roots = document["scenes"][0]["nodes"].as_array().unwrap();
+    let names: Vec<&str> = roots
+        .iter()
+        .map(|n| {
+            document["nodes"][n.as_u64().unwrap() as usize]["name"]
+                .as_str()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(names, vec!["armature", "strands"]);
+    let armature = roots[0].as_u64().unwrap() as usize;
+    assert_eq!(skin["skeleton"], armature);
+    assert_eq!(
+        document["nodes"][armature]["children"],
+        serde_json::json!([joints[0]])
+    );
+    let root_joint = &document["nodes"][joints[0].as_u64().unwrap() as usize];
+    assert_eq!(root_joint["children"].as_array().unwrap().len(), 1);
+
+    let strands = document["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|n| n["name"] == "strands")
+        .unwrap();
+    assert_eq!(strands["skin"], 0);
+    let attributes = &document["meshes"][strands["mesh"].as_u64().unwrap() as usize]["primitives"]
+        [0]["attributes"];
+    let weights = read_floats(&document, &bin, &attributes["WEIGHTS_0"]);
+    for vertex in weights.chunks_exact(4) {
+        assert_relative_eq!(vertex.iter().sum::<f32>(), 1.0, epsilon = 1e-5);
+    }
+    let joints_accessor = attributes["JOINTS_0"].as_u64().unwrap() as usize;
+    assert_eq!(
+        document["accessors"][joints_accessor]["componentType"],
+        5123
+    );
+}
+
+#[test]
+fn test_skinned_roots_share_armature() {
+    // Two disconnected trees
+    let swc = "\
+1 3 0 0 0 0.1 -1
+2 3 0 1 0 0.1 1
+3 3 2 0 0 0.1 -1
+4 3 2 1 0 0.1 3
+";
+    let skeleton = read_swc(swc.as_bytes()).unwrap();
+    let config = GltfConfig {
+        joint_interval: Some(1),
+        ..Default::default()
+    };
+    let (document, _) = export(&skeleton, &config);
+
+    let skin = &document["skins"][0];
+    let armature = skin["skeleton"].as_u64().unwrap() as usize;
+    assert_eq!(document["nodes"][armature]["name"], "armature");
+    assert!(
+        document["scenes"][0]["nodes"]
+            .as_array()
+            .unwrap()
+            .contains(&Value::from(armature))
+    );
+
+    // Both root joints hang off the armature, and no joint is a scene root
+    let joints = skin["joints"].as_array().unwrap();
+    assert_eq!(joints.len(), 4);
+    assert_eq!(
+        document["nodes"][armature]["children"],
+        serde_json::json!([joints[0], joints[2]])
+    );
+    for joint in joints {
+        assert!(
+            !document["scenes"][0]["nodes"]
+                .as_array()
+                .unwrap()
+                .contains(joint)
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use approx::assert_relative_eq;
+use std::sync::Arc;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{GrowthConfig, GrowthCurve, TurtleConfig, TurtleInterpreter};
+
+fn setup(growth: GrowthConfig) -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig {
+        initial_width: 1.0,
+        growth,
+        ..Default::default()
+    });
+
+    for sym in ["F", "[", "]", "~"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    (interpreter, interner)
+}
+
+/// Pushes `(symbol, age)` pairs into a fresh state at time zero.
+fn state(modules: &[(&str, f64)], interner: &SymbolTable) -> SymbiosState {
+    let mut state = SymbiosState::new();
+    for &(sym, age) in modules {
+        let id = interner.resolve_id(sym).unwrap();
+        state.push(id, age, &[]).unwrap();
+    }
+    state
+}
+
+#[test]
+fn test_linear_growth_scales_draw_length() {
+    let (interpreter, interner) = setup(GrowthConfig::default());
+    // The second segment was born one time unit after the first
+    let state = state(&[("F", 1.0), ("F", 0.0)], &interner);
+
+    let skeleton = interpreter.build_skeleton_at(&state, 0.5);
+    let end = skeleton.strands[0].last().unwrap().position;
+    assert_relative_eq!(end.y, 1.0 + 0.5, epsilon = 1e-5);
+
+    let grown = interpreter.build_skeleton_at(&state, 10.0);
+    let end = grown.strands[0].last().unwrap().position;
+    assert_relative_eq!(end.y, 2.0, epsilon = 1e-5);
+}
+
+#[test]
+fn test_unborn_modules_contribute_nothing() {
+    let (interpreter, interner) = setup(GrowthConfig::default());
+    let state = state(&[("F", 0.0), ("F", 0.0)], &interner);
+
+    let skeleton = interpreter.build_skeleton_at(&state, -1.0);
+    let end = skeleton.strands[0].last().unwrap().position;
+    assert_relative_eq!(end.y, 0.0, epsilon = 1e-5);
+}
+
+#[test]
+fn test_width_and_prop_scale_follow_curves() {
+    let (interpreter, interner) = setup(GrowthConfig {
+        length: GrowthCurve::Linear { duration: 0.0 },
+        width: GrowthCurve::Linear { duration: 2.0 },
+        prop_scale: GrowthCurve::Custom(Arc::new(|age| age * 10.0)),
+    });
+    let state = state(&[("F", 1.0), ("~", 0.25)], &interner);
+
+    let skeleton = interpreter.build_skeleton_at(&state, 0.0);
+    let strand = &skeleton.strands[0];
+    assert_relative_eq!(strand.last().unwrap().position.y, 1.0, epsilon = 1e-5);
+    assert_relative_eq!(strand.last().unwrap().radius, 0.5 * 0.5, epsilon = 1e-5);
+    assert_relative_eq!(strand[0].radius, 0.5 * 0.5, epsilon = 1e-5);
+    assert_relative_eq!(skeleton.props[0].scale.x, 2.5, epsilon = 1e-5);
+}
+
+#[test]
+fn test_branch_base_matches_parent_width() {
+    let (interpreter, interner) = setup(GrowthConfig::default());
+    let state = state(
+        &[("F", 0.5), ("[", 0.0), ("F", 0.0), ("]", 0.0), ("F", 0.25)],
+        &interner,
+    );
+
+    let skeleton = interpreter.build_skeleton_at(&state, 0.0);
+    // Branch and resumed trunk both start with the radius of the trunk's last Draw
+    assert_relative_eq!(skeleton.strands[1][0].radius, 0.25, epsilon = 1e-5);
+    assert_relative_eq!(skeleton.strands[2][0].radius, 0.25, epsilon = 1e-5);
+    assert_relative_eq!(
+        skeleton.strands[2].last().unwrap().radius,
+        0.125,
+        epsilon = 1e-5
+    );
+}
+
+#[test]
+fn test_sigmoid_is_monotonic_and_bounded() {
+    let curve = GrowthCurve::Sigmoid {
+        duration: 2.0,
+        steepness: 8.0,
+    };
+    assert_relative_eq!(curve.evaluate(0.0), 0.0, epsilon = 1e-6);
+    assert_relative_eq!(curve.evaluate(1.0), 0.5, epsilon = 1e-5);
+    assert_relative_eq!(curve.evaluate(2.0), 1.0, epsilon = 1e-6);
+    assert_relative_eq!(curve.evaluate(5.0), 1.0, epsilon = 1e-6);
+
+    let mut previous = -1.0;
+    for k in 0..=20 {
+        let value = curve.evaluate(k as f32 * 0.1);
+        assert!(value >= previous);
+        previous = value;
+    }
+}
+
+#[test]
+fn test_build_skeleton_ignores_age() {
+    let (interpreter, interner) = setup(GrowthConfig::default());
+    let state = state(&[("F", 0.0)], &interner);
+
+    let skeleton = interpreter.build_skeleton(&state);
+    assert_relative_eq!(
+        skeleton.strands[0].last().unwrap().position.y,
+        1.0,
+        epsilon = 1e-5
+    );
+}
+
+#[test]
+fn test_ungrown_props_are_not_spawned() {
+    let (interpreter, interner) = setup(GrowthConfig {
+        prop_scale: GrowthCurve::Custom(Arc::new(|age| (age - 1.0).clamp(0.0, 1.0))),
+        ..Default::default()
+    });
+    let state = state(&[("F", 1.0), ("~", 1.0), ("~", 0.0)], &interner);
+
+    // At time 0.5 the first prop is 1.5 old and partly grown, the second only 0.5
+    let skeleton = interpreter.build_skeleton_at(&state, 0.5);
+    assert_eq!(skeleton.props.len(), 1);
+    assert_relative_eq!(skeleton.props[0].scale.x, 0.5, epsilon = 1e-5);
+
+    let grown = interpreter.build_skeleton_at(&state, 3.0);
+    assert_eq!(grown.props.len(), 2);
+}
@@ -0,0 +1,123 @@
+use approx::assert_relative_eq;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{JitterConfig, Skeleton, TurtleConfig, TurtleInterpreter};
+
+fn setup(jitter: Option<JitterConfig>) -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig {
+        jitter,
+        ..Default::default()
+    });
+
+    for sym in ["F", "+", "-", "!", "[", "]"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    (interpreter, interner)
+}
+
+fn run(sequence: &str, interpreter: &TurtleInterpreter, interner: &SymbolTable) -> Skeleton {
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        if let Some(start) = token.find('(') {
+            let id = interner.resolve_id(&token[..start]).unwrap();
+            let val: f64 = token[start + 1..token.len() - 1].parse().unwrap();
+            state.push(id, 0.0, &[val]).unwrap();
+        } else {
+            let id = interner.resolve_id(token).unwrap();
+            state.push(id, 0.0, &[]).unwrap();
+        }
+    }
+    interpreter.build_skeleton(&state)
+}
+
+fn jitter(seed: u64) -> Option<JitterConfig> {
+    Some(JitterConfig {
+        angle: 20.0f32.to_radians(),
+        step: 0.3,
+        width: 0.5,
+        seed,
+    })
+}
+
+fn positions(skeleton: &Skeleton) -> Vec<glam::Vec3> {
+    skeleton
+        .strands
+        .iter()
+        .flat_map(|s| s.iter().map(|p| p.position))
+        .collect()
+}
+
+const TREE: &str = "F [ + F F ] [ - F F ] F";
+
+#[test]
+fn test_no_jitter_is_exact() {
+    let (interpreter, interner) = setup(None);
+    let skeleton = run("F(2) +(90) F(3)", &interpreter, &interner);
+    let end = skeleton.strands[0][2].position;
+    assert_relative_eq!(end.x, -3.0, epsilon = 1e-5);
+    assert_relative_eq!(end.y, 2.0, epsilon = 1e-5);
+}
+
+#[test]
+fn test_jitter_is_deterministic_per_seed() {
+    let (a, interner) = setup(jitter(7));
+    let (b, _) = setup(jitter(7));
+    let (c, _) = setup(jitter(8));
+
+    let pa = positions(&run(TREE, &a, &interner));
+    let pb = positions(&run(TREE, &b, &interner));
+    let pc = positions(&run(TREE, &c, &interner));
+
+    assert_eq!(pa, pb);
+    assert_ne!(pa, pc);
+}
+
+#[test]
+fn test_jitter_amplitudes_are_bounded() {
+    let (interpreter, interner) = setup(Some(JitterConfig {
+        angle: 0.0,
+        step: 0.25,
+        width: 0.5,
+        seed: 3,
+    }));
+    let skeleton = run("!(1) F(4) F(4) F(4) F(4)", &interpreter, &interner);
+
+    let strand = &skeleton.strands[0];
+    for pair in strand.windows(2) {
+        let len = pair[0].position.distance(pair[1].position);
+        assert!((3.0..=5.0).contains(&len), "step {len} outside ±25%");
+        // Zero angle jitter keeps the strand straight
+        assert_relative_eq!(pair[1].position.x, 0.0, epsilon = 1e-5);
+    }
+    let radius = strand[1].radius;
+    assert!(
+        (0.25..=0.75).contains(&radius),
+        "radius {radius} outside ±50%"
+    );
+}
+
+#[test]
+fn test_editing_one_branch_preserves_others() {
+    let (interpreter, interner) = setup(jitter(42));
+
+    let original = run(TREE, &interpreter, &interner);
+    // Lengthen the first branch only
+    let edited = run("F [ + F F F F(2) ] [ - F F ] F", &interpreter, &interner);
+
+    // Strand layout ends with: second branch, then resumed trunk
+    let n_orig = original.strands.len();
+    let n_edit = edited.strands.len();
+    for offset in 1..=2 {
+        let a = &original.strands[n_orig - offset];
+        let b = &edited.strands[n_edit - offset];
+        assert_eq!(a.len(), b.len());
+        for (pa, pb) in a.iter().zip(b) {
+            assert_eq!(pa.position, pb.position);
+        }
+    }
+
+    // The edited branch itself differs
+    assert_ne!(original.strands[1].len(), edited.strands[1].len());
+}
@@ -0,0 +1,167 @@
+use approx::assert_relative_eq;
+use std::sync::{Arc, Mutex};
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{
+    CancellationToken, DiagnosticKind, InterpretError, Limit, ResourceLimits, TurtleConfig,
+    TurtleInterpreter,
+};
+
+fn setup(limits: ResourceLimits) -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig {
+        limits,
+        ..Default::default()
+    });
+
+    for sym in ["F", "f", "+", "[", "]", "~"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    (interpreter, interner)
+}
+
+fn parse(sequence: &str, interner: &SymbolTable) -> SymbiosState {
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let id = interner.resolve_id(token).unwrap();
+        state.push(id, 0.0, &[]).unwrap();
+    }
+    state
+}
+
+fn stopped_by(interpreter: &TurtleInterpreter, state: &SymbiosState) -> Option<(usize, Limit)> {
+    let (_, report) = interpreter.try_build_skeleton(state).unwrap();
+    report.stopped().map(|d| match d.kind {
+        DiagnosticKind::LimitExceeded { limit } => (d.module_index, limit),
+        other => panic!("unexpected stop {other:?}"),
+    })
+}
+
+#[test]
+fn test_unlimited_by_default() {
+    let (interpreter, interner) = setup(ResourceLimits::default());
+    let state = parse(&"F ~ ".repeat(1000), &interner);
+
+    let (skeleton, report) = interpreter.try_build_skeleton(&state).unwrap();
+    assert!(report.stopped().is_none());
+    assert_eq!(skeleton.strands[0].len(), 1001);
+    assert_eq!(skeleton.props.len(), 1000);
+}
+
+#[test]
+fn test_point_limit() {
+    let (interpreter, interner) = setup(ResourceLimits {
+        max_points: Some(4),
+        ..Default::default()
+    });
+    let state = parse("F F F F F F", &interner);
+
+    let skeleton = interpreter.build_skeleton(&state);
+    let points: usize = skeleton.strands.iter().map(Vec::len).sum();
+    assert_eq!(points, 4);
+    assert_eq!(stopped_by(&interpreter, &state), Some((3, Limit::Points)));
+}
+
+#[test]
+fn test_strand_limit() {
+    let (interpreter, interner) = setup(ResourceLimits {
+        max_strands: Some(3),
+        ..Default::default()
+    });
+    let state = parse("F [ + F ] [ F ] F", &interner);
+
+    let skeleton = interpreter.build_skeleton(&state);
+    assert_eq!(skeleton.strands.len(), 3);
+    assert_eq!(stopped_by(&interpreter, &state), Some((5, Limit::Strands)));
+}
+
+#[test]
+fn test_prop_limit() {
+    let (interpreter, interner) = setup(ResourceLimits {
+        max_props: Some(2),
+        ..Default::default()
+    });
+    let state = parse("~ F ~ F ~ F", &interner);
+
+    let skeleton = interpreter.build_skeleton(&state);
+    assert_eq!(skeleton.props.len(), 2);
+    assert_eq!(
+        skeleton.strands[0].len(),
+        3,
+        "Output after the limit is dropped"
+    );
+    assert_eq!(stopped_by(&interpreter, &state), Some((4, Limit::Props)));
+}
+
+#[test]
+fn test_drawn_length_limit_ignores_moves() {
+    let (interpreter, interner) = setup(ResourceLimits {
+        max_drawn_length: Some(2.0),
+        ..Default::default()
+    });
+    let state = parse("F f f F F", &interner);
+
+    let skeleton = interpreter.build_skeleton(&state);
+    let tip = skeleton.strands.last().unwrap().last().unwrap().position;
+    assert_relative_eq!(tip.y, 4.0, epsilon = 1e-5);
+    assert_eq!(
+        stopped_by(&interpreter, &state),
+        Some((4, Limit::DrawnLength))
+    );
+}
+
+#[test]
+fn test_strict_mode_reports_limit_as_error() {
+    let mut interner = SymbolTable::new();
+    interner.intern("F").unwrap();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig {
+        limits: ResourceLimits {
+            max_points: Some(2),
+            ..Default::default()
+        },
+        strict: true,
+        ..Default::default()
+    });
+    interpreter.populate_standard_symbols(&interner);
+
+    let err = interpreter
+        .try_build_skeleton(&parse("F F F", &interner))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        InterpretError::Strict(d) if d.kind == DiagnosticKind::LimitExceeded { limit: Limit::Points }
+    ));
+}
+
+#[test]
+fn test_cancelled_token_stops_interpretation() {
+    let token = CancellationToken::new();
+    let (interpreter, interner) = setup(ResourceLimits::default());
+    let interpreter = interpreter.with_cancellation(token.clone());
+    let state = parse(&"F ".repeat(1000), &interner);
+
+    let mut cursor = interpreter.cursor(&state);
+    assert!(!cursor.advance(300));
+    token.cancel();
+    assert!(cursor.advance(1000), "Cursor finishes at the next poll");
+
+    let stop = cursor.report().stopped().unwrap();
+    assert_eq!(stop.kind, DiagnosticKind::Cancelled);
+    assert_eq!(stop.module_index, 512);
+    assert_eq!(cursor.finish().strands[0].len(), 513);
+}
+
+#[test]
+fn test_progress_callback() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let (interpreter, interner) = setup(ResourceLimits::default());
+    let interpreter = interpreter.with_progress({
+        let seen = seen.clone();
+        move |fraction| seen.lock().unwrap().push(fraction)
+    });
+    let state = parse(&"F ".repeat(1024), &interner);
+
+    interpreter.build_skeleton(&state);
+    assert_eq!(*seen.lock().unwrap(), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+}
@@ -0,0 +1,127 @@
+use approx::assert_relative_eq;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{MeshConfig, SkeletonMesh, TurtleConfig, TurtleInterpreter};
+
+fn setup() -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+
+    interner.intern("F").unwrap();
+    interner.intern("+").unwrap();
+    interner.intern("[").unwrap();
+    interner.intern("]").unwrap();
+    interner.intern(",").unwrap();
+
+    interpreter.populate_standard_symbols(&interner);
+    (interpreter, interner)
+}
+
+#[test]
+fn test_single_segment_vertex_and_triangle_counts() {
+    let (interpreter, interner) = setup();
+    let f_id = interner.resolve_id("F").unwrap();
+
+    let mut state = SymbiosState::new();
+    state.push(f_id, 0.0, &[2.0]).unwrap();
+    let skeleton = interpreter.build_skeleton(&state);
+
+    let config = MeshConfig {
+        resolution: 6,
+        end_caps: false,
+    };
+    let mesh = SkeletonMesh::build(&skeleton, &config);
+
+    // 2 rings of (6 + 1 seam) vertices, 6 quads = 12 triangles
+    assert_eq!(mesh.vertex_count(), 14);
+    assert_eq!(mesh.triangle_count(), 12);
+    assert_eq!(mesh.normals.len(), mesh.vertex_count());
+    assert_eq!(mesh.uvs.len(), mesh.vertex_count());
+    assert_eq!(mesh.colors.len(), mesh.vertex_count());
+    assert_eq!(mesh.material_ids.len(), mesh.vertex_count());
+
+    // Ring vertices lie at the point radius from the strand axis
+    let radius = skeleton.strands[0][0].radius;
+    for pos in &mesh.positions {
+        assert_relative_eq!(
+            (pos.x * pos.x + pos.z * pos.z).sqrt(),
+            radius,
+            epsilon = 1e-5
+        );
+    }
+
+    let capped = SkeletonMesh::build(
+        &skeleton,
+        &MeshConfig {
+            resolution: 6,
+            end_caps: true,
+        },
+    );
+    // Each cap adds a centre vertex plus 6 rim vertices and 6 triangles
+    assert_eq!(capped.vertex_count(), 14 + 2 * 7);
+    assert_eq!(capped.triangle_count(), 12 + 2 * 6);
+}
+
+#[test]
+fn test_triangles_face_outward() {
+    let (interpreter, interner) = setup();
+    let f_id = interner.resolve_id("F").unwrap();
+    let plus_id = interner.resolve_id("+").unwrap();
+
+    let mut state = SymbiosState::new();
+    state.push(f_id, 0.0, &[1.0]).unwrap();
+    state.push(plus_id, 0.0, &[30.0]).unwrap();
+    state.push(f_id, 0.0, &[1.0]).unwrap();
+    let skeleton = interpreter.build_skeleton(&state);
+
+    let mesh = SkeletonMesh::build(&skeleton, &MeshConfig::default());
+    let indices = mesh.indices();
+    assert_eq!(indices.len(), mesh.triangle_count() * 3);
+
+    for tri in indices.chunks(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| mesh.positions[tri[k] as usize]);
+        let face_normal = (b - a).cross(c - a);
+        let vertex_normal: glam::Vec3 = tri.iter().map(|&i| mesh.normals[i as usize]).sum();
+        assert!(
+            face_normal.dot(vertex_normal) > 0.0,
+            "Triangle winding disagrees with vertex normals"
+        );
+    }
+}
+
+#[test]
+fn test_submeshes_split_by_material() {
+    let (interpreter, interner) = setup();
+    let f_id = interner.resolve_id("F").unwrap();
+    let mat_id = interner.resolve_id(",").unwrap();
+    let push_id = interner.resolve_id("[").unwrap();
+    let pop_id = interner.resolve_id("]").unwrap();
+
+    let mut state = SymbiosState::new();
+    // F [ ,(2) F ] F
+    state.push(f_id, 0.0, &[1.0]).unwrap();
+    state.push(push_id, 0.0, &[]).unwrap();
+    state.push(mat_id, 0.0, &[2.0]).unwrap();
+    state.push(f_id, 0.0, &[1.0]).unwrap();
+    state.push(pop_id, 0.0, &[]).unwrap();
+    state.push(f_id, 0.0, &[1.0]).unwrap();
+    let skeleton = interpreter.build_skeleton(&state);
+
+    let config = MeshConfig {
+        resolution: 4,
+        end_caps: false,
+    };
+    let mesh = SkeletonMesh::build(&skeleton, &config);
+
+    let ids: Vec<u8> = mesh.submeshes.iter().map(|s| s.material_id).collect();
+    assert_eq!(ids, vec![0, 2]);
+
+    // Two material-0 segments (trunk + resumed trunk), one material-2 segment (branch)
+    assert_eq!(mesh.submeshes[0].indices.len(), 2 * 4 * 6);
+    assert_eq!(mesh.submeshes[1].indices.len(), 4 * 6);
+
+    for submesh in &mesh.submeshes {
+        for &idx in &submesh.indices {
+            assert!((idx as usize) < mesh.vertex_count());
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{
+    MeshConfig, ObjConfig, ObjGeometry, Skeleton, SkeletonMesh, TurtleConfig, TurtleInterpreter,
+    save_obj, write_obj,
+};
+
+fn build(sequence: &[(&str, &[f64])]) -> Skeleton {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+
+    for sym in ["F", "+", "[", "]", ",", "~", "{", "}", ".", "'"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    let mut state = SymbiosState::new();
+    for (token, params) in sequence {
+        let id = interner.resolve_id(token).unwrap();
+        state.push(id, 0.0, params).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+fn export(skeleton: &Skeleton, config: &ObjConfig) -> (String, String) {
+    let (mut obj, mut mtl) = (Vec::new(), Vec::new());
+    write_obj(skeleton, config, "tree.mtl", &mut obj, &mut mtl).unwrap();
+    (
+        String::from_utf8(obj).unwrap(),
+        String::from_utf8(mtl).unwrap(),
+    )
+}
+
+fn lines<'a>(text: &'a str, prefix: &str) -> Vec<&'a str> {
+    text.lines().filter(|l| l.starts_with(prefix)).collect()
+}
+
+#[test]
+fn test_polylines_split_by_material() {
+    let skeleton = build(&[
+        ("F", &[]),
+        ("F", &[]),
+        (",", &[2.0]),
+        ("F", &[]),
+        ("[", &[]),
+        ("+", &[]),
+        (",", &[0.0]),
+        ("F", &[]),
+        ("]", &[]),
+    ]);
+    let config = ObjConfig {
+        geometry: ObjGeometry::Polylines,
+        ..Default::default()
+    };
+    let (obj, mtl) = export(&skeleton, &config);
+
+    assert!(obj.contains("mtllib tree.mtl"));
+    let points: usize = skeleton.strands.iter().map(Vec::len).sum();
+    assert_eq!(lines(&obj, "v ").len(), points);
+    assert_eq!(
+        lines(&obj, "usemtl "),
+        vec!["usemtl material_0", "usemtl material_2"]
+    );
+    // Trunk run of material 0, branch, then the trunk's material 2 segment
+    assert_eq!(lines(&obj, "l "), vec!["l 1 2 3", "l 5 6", "l 3 4"]);
+    assert_eq!(
+        lines(&mtl, "newmtl "),
+        vec!["newmtl material_0", "newmtl material_2"]
+    );
+    assert_eq!(lines(&mtl, "Kd ").len(), 2);
+}
+
+#[test]
+fn test_tubes_match_mesh() {
+    let skeleton = build(&[("F", &[]), ("+", &[]), (",", &[1.0]), ("F", &[])]);
+    let mesh_config = MeshConfig::default();
+    let mesh = SkeletonMesh::build(&skeleton, &mesh_config);
+    let config = ObjConfig {
+        geometry: ObjGeometry::Tubes(mesh_config),
+        ..Default::default()
+    };
+    let (obj, _) = export(&skeleton, &config);
+
+    assert_eq!(lines(&obj, "v ").len(), mesh.vertex_count());
+    assert_eq!(lines(&obj, "vt ").len(), mesh.vertex_count());
+    assert_eq!(lines(&obj, "vn ").len(), mesh.vertex_count());
+    assert_eq!(lines(&obj, "f ").len(), mesh.triangle_count());
+    assert_eq!(lines(&obj, "usemtl ").len(), mesh.submeshes.len());
+
+    let max_index = lines(&obj, "f ")
+        .iter()
+        .flat_map(|l| l.split_whitespace().skip(1))
+        .map(|corner| corner.split('/').next().unwrap().parse::<usize>().unwrap())
+        .max()
+        .unwrap();
+    assert_eq!(max_index, mesh.vertex_count());
+}
+
+#[test]
+fn test_props_and_polygons() {
+    let skeleton = build(&[
+        ("F", &[]),
+        ("~", &[3.0]),
+        (",", &[4.0]),
+        ("'", &[0.5, 0.25, 1.0, 0.5]),
+        ("{", &[]),
+        (".", &[]),
+        ("+", &[]),
+        ("F", &[]),
+        (".", &[]),
+        ("+", &[]),
+        ("F", &[]),
+        (".", &[]),
+        ("}", &[]),
+        ("~", &[1.0]),
+    ]);
+    let config = ObjConfig {
+        geometry: ObjGeometry::Polylines,
+        ..Default::default()
+    };
+    let (obj, mtl) = export(&skeleton, &config);
+
+    assert_eq!(
+        lines(&obj, "g "),
+        vec!["g strands", "g polygons", "g prop_3_0", "g prop_1_1"]
+    );
+    assert_eq!(lines(&obj, "f ").len(), 1);
+    assert!(mtl.contains("newmtl material_4\nKd 0.5 0.25 1\nd 0.5\n"));
+
+    let without = ObjConfig {
+        geometry: ObjGeometry::Polylines,
+        polygons: false,
+        props: false,
+    };
+    let (obj, _) = export(&skeleton, &without);
+    assert_eq!(lines(&obj, "g "), vec!["g strands"]);
+}
+
+#[test]
+fn test_save_obj_writes_companion_mtl() {
+    let skeleton = build(&[("F", &[])]);
+    let dir = std::env::temp_dir().join(format!("symbios_obj_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("tree.obj");
+    save_obj(&skeleton, &ObjConfig::default(), &path).unwrap();
+    let obj = std::fs::read_to_string(&path).unwrap();
+    let mtl = std::fs::read_to_string(dir.join("tree.mtl")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(obj.contains("mtllib tree.mtl"));
+    assert!(mtl.contains("newmtl material_0"));
+}
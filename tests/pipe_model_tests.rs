@@ -0,0 +1,114 @@
+use approx::assert_relative_eq;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{PipeModelConfig, Skeleton, TurtleConfig, TurtleInterpreter};
+
+fn build(sequence: &str) -> Skeleton {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+    for sym in ["F", "f", "+", "-", "!", "[", "]"] {
+        interner.intern(sym).unwrap();
+    }
+    interpreter.populate_standard_symbols(&interner);
+
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        if let Some(start) = token.find('(') {
+            let id = interner.resolve_id(&token[..start]).unwrap();
+            let val: f64 = token[start + 1..token.len() - 1].parse().unwrap();
+            state.push(id, 0.0, &[val]).unwrap();
+        } else {
+            let id = interner.resolve_id(token).unwrap();
+            state.push(id, 0.0, &[]).unwrap();
+        }
+    }
+    interpreter.build_skeleton(&state)
+}
+
+#[test]
+fn test_da_vinci_junction() {
+    let mut skeleton = build("F [ + F ] F");
+    skeleton.apply_pipe_model(&PipeModelConfig {
+        exponent: 2.0,
+        tip_radius: 0.1,
+        clamp_to_specified: false,
+    });
+
+    // Branch and resumed trunk end in free tips
+    for strand in [1, 2] {
+        for point in &skeleton.strands[strand] {
+            assert_relative_eq!(point.radius, 0.1, epsilon = 1e-6);
+        }
+    }
+
+    // Trunk carries both pipes: r = sqrt(0.1^2 + 0.1^2)
+    for point in &skeleton.strands[0] {
+        assert_relative_eq!(point.radius, 0.02f32.sqrt(), epsilon = 1e-6);
+    }
+}
+
+#[test]
+fn test_radius_grows_toward_root() {
+    let mut skeleton = build("F [ + F ] F [ - F ] F [ + F ] F");
+    skeleton.apply_pipe_model(&PipeModelConfig {
+        exponent: 2.5,
+        tip_radius: 0.05,
+        clamp_to_specified: false,
+    });
+
+    let base = skeleton.strands[0][0].radius;
+    let expected = (4.0 * 0.05f32.powf(2.5)).powf(1.0 / 2.5);
+    assert_relative_eq!(base, expected, epsilon = 1e-6);
+
+    // Every child base is no thicker than its attachment point
+    for (i, info) in skeleton.strand_info.iter().enumerate() {
+        if let Some(parent) = info.parent {
+            let parent_radius = skeleton.strands[parent][info.parent_point].radius;
+            assert!(skeleton.strands[i][0].radius <= parent_radius + 1e-6);
+        }
+    }
+}
+
+#[test]
+fn test_clamp_to_specified_width() {
+    let mut skeleton = build("!(0.1) F [ + F ] [ - F ] F");
+    skeleton.apply_pipe_model(&PipeModelConfig {
+        exponent: 2.0,
+        tip_radius: 0.04,
+        clamp_to_specified: true,
+    });
+
+    // `!(0.1)` specifies a radius of 0.05; three tips would otherwise reach ~0.069
+    let base = skeleton.strands[0][0].radius;
+    assert_relative_eq!(base, 0.05, epsilon = 1e-6);
+    assert_relative_eq!(skeleton.strands[1][1].radius, 0.04, epsilon = 1e-6);
+}
+
+#[test]
+fn test_empty_branches_carry_no_flow() {
+    let mut skeleton = build("F [ ] F");
+    skeleton.apply_pipe_model(&PipeModelConfig {
+        exponent: 2.0,
+        tip_radius: 0.1,
+        clamp_to_specified: false,
+    });
+
+    assert_relative_eq!(skeleton.strands[0][0].radius, 0.1, epsilon = 1e-6);
+}
+
+#[test]
+fn test_move_strand_is_independent() {
+    let mut skeleton = build("F f F");
+    skeleton.apply_pipe_model(&PipeModelConfig {
+        exponent: 2.0,
+        tip_radius: 0.1,
+        clamp_to_specified: false,
+    });
+
+    // Both segments end in free tips; the Move does not join them
+    assert_eq!(skeleton.strands.len(), 2);
+    for strand in &skeleton.strands {
+        for point in strand {
+            assert_relative_eq!(point.radius, 0.1, epsilon = 1e-6);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use approx::assert_relative_eq;
+use glam::Vec3;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{SkeletonPolygon, TurtleConfig, TurtleInterpreter};
+
+fn setup() -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+
+    for sym in ["f", "+", "-", "[", "]", "{", "}", ".", "'"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    (interpreter, interner)
+}
+
+/// Pushes a whitespace-separated sequence like "{ . f(1) +(90) . }" onto a state.
+fn build_state(sequence: &str, interner: &SymbolTable) -> SymbiosState {
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        if let Some(start) = token.find('(') {
+            let id = interner.resolve_id(&token[..start]).unwrap();
+            let params: Vec<f64> = token[start + 1..token.len() - 1]
+                .split(',')
+                .map(|v| v.parse().unwrap())
+                .collect();
+            state.push(id, 0.0, &params).unwrap();
+        } else {
+            let id = interner.resolve_id(token).unwrap();
+            state.push(id, 0.0, &[]).unwrap();
+        }
+    }
+    state
+}
+
+fn triangulated_area(polygon: &SkeletonPolygon) -> f32 {
+    polygon
+        .triangulate()
+        .iter()
+        .map(|t| {
+            let [a, b, c] = t.map(|i| polygon.vertices[i as usize]);
+            (b - a).cross(c - a).length() * 0.5
+        })
+        .sum()
+}
+
+#[test]
+fn test_square_polygon() {
+    let (interpreter, interner) = setup();
+    let state = build_state(
+        "'(0,1,0) { . f(1) +(90) . f(1) +(90) . f(1) +(90) . }",
+        &interner,
+    );
+
+    let skeleton = interpreter.build_skeleton(&state);
+
+    assert_eq!(skeleton.polygons.len(), 1);
+    let polygon = &skeleton.polygons[0];
+    assert_eq!(polygon.vertices.len(), 4);
+    assert_relative_eq!(polygon.color.y, 1.0);
+    assert_relative_eq!(polygon.color.x, 0.0);
+
+    assert_eq!(polygon.triangulate().len(), 2);
+    assert_relative_eq!(triangulated_area(polygon), 1.0, epsilon = 1e-5);
+}
+
+#[test]
+fn test_polygon_vertices_recorded_inside_branches() {
+    let (interpreter, interner) = setup();
+    // ABOP-style leaf: vertices recorded from nested branches belong to the enclosing polygon
+    let state = build_state("{ . [ +(90) f(1) . ] f(1) . [ -(90) f(1) . ] }", &interner);
+
+    let skeleton = interpreter.build_skeleton(&state);
+
+    assert_eq!(skeleton.polygons.len(), 1);
+    let vertices = &skeleton.polygons[0].vertices;
+    assert_eq!(vertices.len(), 4);
+    assert!(vertices[0].abs_diff_eq(Vec3::ZERO, 1e-5));
+    assert!(vertices[1].abs_diff_eq(Vec3::new(-1.0, 0.0, 0.0), 1e-5));
+    assert!(vertices[2].abs_diff_eq(Vec3::new(0.0, 1.0, 0.0), 1e-5));
+    assert!(vertices[3].abs_diff_eq(Vec3::new(1.0, 1.0, 0.0), 1e-5));
+}
+
+#[test]
+fn test_nested_polygons() {
+    let (interpreter, interner) = setup();
+    let state = build_state(
+        "{ . f(1) . { . +(90) f(1) . f(1) . } +(90) f(1) . }",
+        &interner,
+    );
+
+    let skeleton = interpreter.build_skeleton(&state);
+
+    // Inner polygon closes first
+    assert_eq!(skeleton.polygons.len(), 2);
+    assert_eq!(skeleton.polygons[0].vertices.len(), 3);
+    assert_eq!(skeleton.polygons[1].vertices.len(), 3);
+}
+
+#[test]
+fn test_degenerate_and_unbalanced_polygons_are_dropped() {
+    let (interpreter, interner) = setup();
+    // Two vertices, a stray close, and an unclosed polygon
+    let state = build_state("{ . f(1) . } } { . f(1) . f(1) .", &interner);
+
+    let skeleton = interpreter.build_skeleton(&state);
+    assert!(skeleton.polygons.is_empty());
+}
+
+#[test]
+fn test_concave_triangulation() {
+    // L-shaped outline with area 3
+    let polygon = SkeletonPolygon {
+        vertices: vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(2.0, 1.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(1.0, 2.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+        ],
+        color: glam::Vec4::ONE,
+        material_id: 0,
+    };
+
+    let triangles = polygon.triangulate();
+    assert_eq!(triangles.len(), 4);
+    assert_relative_eq!(triangulated_area(&polygon), 3.0, epsilon = 1e-5);
+
+    // Every triangle is wound consistently with the polygon normal
+    let normal = polygon.normal();
+    assert_relative_eq!(normal.z, 1.0, epsilon = 1e-5);
+    for t in &triangles {
+        let [a, b, c] = t.map(|i| polygon.vertices[i as usize]);
+        assert!((b - a).cross(c - a).dot(normal) > 0.0);
+    }
+
+    // Clockwise order flips the normal but still triangulates fully
+    let mut reversed = polygon.clone();
+    reversed.vertices.reverse();
+    assert_relative_eq!(reversed.normal().z, -1.0, epsilon = 1e-5);
+    assert_relative_eq!(triangulated_area(&reversed), 3.0, epsilon = 1e-5);
+}
@@ -0,0 +1,160 @@
+use approx::assert_relative_eq;
+use glam::Vec3;
+use std::sync::Arc;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{
+    AngleUnit, InterpretationProfile, Skeleton, Tropism, TropismField, TurtleConfig,
+    TurtleInterpreter, TurtleOp,
+};
+
+fn interner() -> SymbolTable {
+    let mut interner = SymbolTable::new();
+    for sym in ["F", "f", "+", "-", "&", "[", "]", "~", "J"] {
+        interner.intern(sym).unwrap();
+    }
+    interner
+}
+
+fn run(sequence: &str, interpreter: &TurtleInterpreter, interner: &SymbolTable) -> Skeleton {
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let (sym, params) = match token.find('(') {
+            Some(start) => (
+                &token[..start],
+                vec![token[start + 1..token.len() - 1].parse().unwrap()],
+            ),
+            None => (token, vec![]),
+        };
+        let id = interner.resolve_id(sym).unwrap();
+        state.push(id, 0.0, &params).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+fn tip(skeleton: &Skeleton) -> Vec3 {
+    skeleton.strands.last().unwrap().last().unwrap().position
+}
+
+#[test]
+fn test_standard_profile_matches_populate_standard_symbols() {
+    let interner = interner();
+    let mut manual = TurtleInterpreter::new(TurtleConfig::default());
+    manual.populate_standard_symbols(&interner);
+    let bound = InterpretationProfile::standard().bind(&interner);
+
+    let sequence = "F [ + F ~ ] & F - f F";
+    let a = run(sequence, &manual, &interner);
+    let b = run(sequence, &bound, &interner);
+    assert_eq!(a.strands.len(), b.strands.len());
+    assert_eq!(tip(&a), tip(&b));
+    assert_eq!(a.props.len(), b.props.len());
+}
+
+#[test]
+fn test_presets_round_trip_through_json() {
+    for profile in [
+        InterpretationProfile::standard(),
+        InterpretationProfile::abop(),
+        InterpretationProfile::houdini(),
+    ] {
+        let json = profile.to_json().unwrap();
+        let parsed = InterpretationProfile::from_json(&json).unwrap();
+        assert_eq!(parsed.name, profile.name);
+        assert_eq!(parsed.symbols, profile.symbols);
+        assert_eq!(parsed.config.default_angle, profile.config.default_angle);
+    }
+}
+
+#[test]
+fn test_partial_json_uses_defaults() {
+    let json = r#"{
+        "name": "sparse",
+        "config": { "default_step": 2.0, "angle_unit": "Radians" },
+        "symbols": { "F": "Draw", "+": { "Yaw": 1.0 }, "~": { "Spawn": 3 } }
+    }"#;
+    let profile = InterpretationProfile::from_json(json).unwrap();
+    assert_eq!(profile.config.default_step, 2.0);
+    assert_eq!(profile.config.angle_unit, AngleUnit::Radians);
+    assert_eq!(
+        profile.config.max_stack_depth,
+        TurtleConfig::default().max_stack_depth
+    );
+    assert_eq!(profile.symbols["~"], TurtleOp::Spawn(3));
+
+    let interner = interner();
+    let interpreter = profile.bind(&interner);
+    let skeleton = run("F ~ - F", &interpreter, &interner);
+    // `-` is unmapped in this profile
+    assert_relative_eq!(tip(&skeleton).y, 4.0, epsilon = 1e-5);
+    assert_eq!(skeleton.props[0].prop_id, 3);
+}
+
+#[test]
+fn test_houdini_turns_opposite_to_abop() {
+    let interner = interner();
+    let abop = InterpretationProfile::abop().bind(&interner);
+    let houdini = InterpretationProfile::houdini().bind(&interner);
+
+    let a = tip(&run("+(90) F", &abop, &interner));
+    let h = tip(&run("+(90) F", &houdini, &interner));
+    assert_relative_eq!(a.x, -h.x, epsilon = 1e-5);
+
+    let skeleton = run("F J", &houdini, &interner);
+    assert_eq!(skeleton.props[0].prop_id, 0);
+}
+
+#[test]
+fn test_radian_angle_unit() {
+    let interner = interner();
+    let mut profile = InterpretationProfile::standard();
+    let degrees = profile.bind(&interner);
+    profile.config.angle_unit = AngleUnit::Radians;
+    let radians = profile.bind(&interner);
+
+    let d = tip(&run("+(90) F", &degrees, &interner));
+    let r = tip(&run(
+        &format!("+({}) F", std::f32::consts::FRAC_PI_2),
+        &radians,
+        &interner,
+    ));
+    assert!(d.abs_diff_eq(r, 1e-5), "{d} != {r}");
+}
+
+#[test]
+fn test_config_round_trips_through_json() {
+    let config = TurtleConfig {
+        default_step: 0.5,
+        tropism: Some(Vec3::NEG_Y),
+        tropisms: vec![Tropism::Point {
+            position: Vec3::new(1.0, 2.0, 3.0),
+            elasticity: 0.1,
+        }],
+        rotation_minimizing_frames: true,
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    let parsed: TurtleConfig = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed.default_step, 0.5);
+    assert_eq!(parsed.tropism, Some(Vec3::NEG_Y));
+    assert_eq!(parsed.tropisms.len(), 1);
+    assert!(parsed.rotation_minimizing_frames);
+}
+
+struct Swirl;
+
+impl TropismField for Swirl {
+    fn sample(&self, position: Vec3) -> Vec3 {
+        Vec3::new(-position.z, 0.0, position.x)
+    }
+}
+
+#[test]
+fn test_closure_backed_config_does_not_serialize() {
+    let mut profile = InterpretationProfile::standard();
+    profile.config.tropisms.push(Tropism::Field {
+        field: Arc::new(Swirl),
+        elasticity: 0.2,
+    });
+    assert!(profile.to_json().is_err());
+}
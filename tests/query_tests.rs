@@ -0,0 +1,109 @@
+use approx::assert_relative_eq;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{QueryKind, TurtleConfig, TurtleInterpreter, apply_query_patches};
+
+fn setup() -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+    for sym in ["F", "+", "[", "]", "?P", "?H", "?U", "A"] {
+        interner.intern(sym).unwrap();
+    }
+    interpreter.populate_standard_symbols(&interner);
+    (interpreter, interner)
+}
+
+#[test]
+fn test_queries_capture_turtle_state() {
+    let (interpreter, interner) = setup();
+    let id = |s| interner.resolve_id(s).unwrap();
+
+    let mut state = SymbiosState::new();
+    state.push(id("F"), 0.0, &[2.0]).unwrap();
+    state.push(id("?P"), 0.0, &[0.0, 0.0, 0.0]).unwrap();
+    state.push(id("+"), 0.0, &[90.0]).unwrap();
+    state.push(id("?H"), 0.0, &[0.0, 0.0, 0.0]).unwrap();
+    state.push(id("?U"), 0.0, &[0.0, 0.0, 0.0]).unwrap();
+
+    let (skeleton, patches) = interpreter.build_skeleton_with_queries(&state);
+    assert_eq!(skeleton.strands.len(), 1);
+    assert_eq!(patches.len(), 3);
+
+    assert_eq!(patches[0].module_index, 1);
+    assert_eq!(patches[0].kind, QueryKind::Position);
+    assert_relative_eq!(patches[0].values[1], 2.0, epsilon = 1e-6);
+
+    // After a left turn the heading points along -X
+    assert_eq!(patches[1].kind, QueryKind::Heading);
+    assert_relative_eq!(patches[1].values[0], -1.0, epsilon = 1e-6);
+    assert_relative_eq!(patches[1].values[1], 0.0, epsilon = 1e-6);
+
+    // Turning around the up vector leaves it unchanged
+    assert_eq!(patches[2].kind, QueryKind::Up);
+    assert_relative_eq!(patches[2].values[2], 1.0, epsilon = 1e-6);
+}
+
+#[test]
+fn test_queries_respect_branching() {
+    let (interpreter, interner) = setup();
+    let id = |s| interner.resolve_id(s).unwrap();
+
+    let mut state = SymbiosState::new();
+    state.push(id("F"), 0.0, &[1.0]).unwrap();
+    state.push(id("["), 0.0, &[]).unwrap();
+    state.push(id("F"), 0.0, &[5.0]).unwrap();
+    state.push(id("?P"), 0.0, &[0.0, 0.0, 0.0]).unwrap();
+    state.push(id("]"), 0.0, &[]).unwrap();
+    state.push(id("?P"), 0.0, &[0.0, 0.0, 0.0]).unwrap();
+
+    let (_, patches) = interpreter.build_skeleton_with_queries(&state);
+    assert_relative_eq!(patches[0].values[1], 6.0, epsilon = 1e-6);
+    assert_relative_eq!(patches[1].values[1], 1.0, epsilon = 1e-6);
+}
+
+#[test]
+fn test_apply_patches_writes_back_into_state() {
+    let (interpreter, interner) = setup();
+    let id = |s| interner.resolve_id(s).unwrap();
+
+    let mut state = SymbiosState::new();
+    state.push(id("A"), 1.5, &[7.0]).unwrap();
+    state.push(id("F"), 0.5, &[3.0]).unwrap();
+    state.push(id("?P"), 0.25, &[0.0, 0.0, 0.0]).unwrap();
+    // A single-parameter query keeps its arity and receives only x
+    state.push(id("?H"), 0.0, &[9.0]).unwrap();
+    state.advance_time(2.0).unwrap();
+
+    let (_, patches) = interpreter.build_skeleton_with_queries(&state);
+    let patched = apply_query_patches(&state, &patches).unwrap();
+
+    assert_eq!(patched.len(), state.len());
+    assert_relative_eq!(patched.current_time, state.current_time);
+
+    let untouched = patched.get_view(0).unwrap();
+    assert_eq!(untouched.params, &[7.0]);
+    assert_relative_eq!(untouched.age, 3.5);
+
+    let position = patched.get_view(2).unwrap();
+    assert_eq!(position.sym, id("?P"));
+    assert_relative_eq!(position.params[1], 3.0, epsilon = 1e-6);
+    assert_relative_eq!(position.age, 2.25);
+
+    let heading = patched.get_view(3).unwrap();
+    assert_eq!(heading.params.len(), 1);
+    assert_relative_eq!(heading.params[0], 0.0, epsilon = 1e-6);
+}
+
+#[test]
+fn test_build_skeleton_ignores_queries() {
+    let (interpreter, interner) = setup();
+    let id = |s| interner.resolve_id(s).unwrap();
+
+    let mut state = SymbiosState::new();
+    state.push(id("F"), 0.0, &[1.0]).unwrap();
+    state.push(id("?P"), 0.0, &[0.0, 0.0, 0.0]).unwrap();
+    state.push(id("F"), 0.0, &[1.0]).unwrap();
+
+    let skeleton = interpreter.build_skeleton(&state);
+    assert_eq!(skeleton.strands.len(), 1);
+    assert_eq!(skeleton.strands[0].len(), 3);
+}
@@ -0,0 +1,142 @@
+use glam::Vec3;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{
+    Skeleton, SkeletonPoint, SkeletonProp, StrandInfo, TurtleConfig, TurtleInterpreter, TurtleSink,
+};
+
+fn setup() -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+
+    for sym in ["F", "f", "+", "&", "[", "]", "~"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    (interpreter, interner)
+}
+
+fn parse(sequence: &str, interner: &SymbolTable) -> SymbiosState {
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let (sym, params) = match token.find('(') {
+            Some(start) => (
+                &token[..start],
+                vec![token[start + 1..token.len() - 1].parse().unwrap()],
+            ),
+            None => (token, vec![]),
+        };
+        let id = interner.resolve_id(sym).unwrap();
+        state.push(id, 0.0, &params).unwrap();
+    }
+    state
+}
+
+/// Streams positions into a flat line-list buffer, as a GPU upload would.
+#[derive(Default)]
+struct LineBuffer {
+    vertices: Vec<Vec3>,
+    infos: Vec<StrandInfo>,
+    last: Vec3,
+    props: usize,
+    events: Vec<&'static str>,
+}
+
+impl TurtleSink for LineBuffer {
+    fn begin_strand(&mut self, point: SkeletonPoint, info: StrandInfo) {
+        self.last = point.position;
+        self.infos.push(info);
+        self.events.push("begin");
+    }
+
+    fn add_point(&mut self, point: SkeletonPoint) {
+        self.vertices.push(self.last);
+        self.vertices.push(point.position);
+        self.last = point.position;
+    }
+
+    fn end_strand(&mut self) {
+        self.events.push("end");
+    }
+
+    fn spawn_prop(&mut self, _prop: SkeletonProp) {
+        self.props += 1;
+    }
+
+    fn branch_push(&mut self) {
+        self.events.push("push");
+    }
+
+    fn branch_pop(&mut self) {
+        self.events.push("pop");
+    }
+}
+
+#[test]
+fn test_custom_sink_matches_skeleton() {
+    let (interpreter, interner) = setup();
+    let state = parse("F [ + F F ~ ] [ & F(0) F ] f F ~", &interner);
+
+    let skeleton = interpreter.build_skeleton(&state);
+    let mut buffer = LineBuffer::default();
+    interpreter.build_into(&state, &mut buffer);
+
+    let segments: usize = skeleton
+        .strands
+        .iter()
+        .map(|s| s.len().saturating_sub(1))
+        .sum();
+    assert_eq!(buffer.vertices.len(), segments * 2);
+    assert_eq!(buffer.infos, skeleton.strand_info);
+    assert_eq!(buffer.props, skeleton.props.len());
+
+    let expected: Vec<Vec3> = skeleton
+        .strands
+        .iter()
+        .flat_map(|s| s.windows(2).flat_map(|w| [w[0].position, w[1].position]))
+        .collect();
+    assert_eq!(buffer.vertices, expected);
+}
+
+#[test]
+fn test_callbacks_are_balanced_and_ordered() {
+    let (interpreter, interner) = setup();
+    let state = parse("F [ F ] F", &interner);
+
+    let mut buffer = LineBuffer::default();
+    interpreter.build_into(&state, &mut buffer);
+
+    assert_eq!(
+        buffer.events,
+        [
+            "begin", "end", "push", "begin", "end", "pop", "begin", "end"
+        ]
+    );
+}
+
+#[test]
+fn test_cursor_into_owns_sink() {
+    let (interpreter, interner) = setup();
+    let state = parse("F F [ + F ] F", &interner);
+
+    let mut cursor = interpreter.cursor_into(&state, LineBuffer::default());
+    cursor.advance(2);
+    assert_eq!(cursor.sink().vertices.len(), 4);
+
+    let (buffer, patches) = cursor.finish_into();
+    assert!(patches.is_empty());
+    assert_eq!(buffer.vertices.len(), 8);
+}
+
+#[test]
+fn test_skeleton_is_a_sink() {
+    let (interpreter, interner) = setup();
+    let state = parse("F [ + F ] F", &interner);
+
+    let mut skeleton = Skeleton::new();
+    interpreter.build_into(&state, &mut skeleton);
+    let built = interpreter.build_skeleton(&state);
+
+    assert_eq!(skeleton.strands.len(), built.strands.len());
+    assert_eq!(skeleton.strand_info, built.strand_info);
+}
@@ -0,0 +1,126 @@
+use approx::assert_relative_eq;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{Skeleton, SmoothingConfig, SplineKind, TurtleConfig, TurtleInterpreter};
+
+fn build(sequence: &str) -> Skeleton {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+    for sym in ["F", "+", "-", "!", "'", "[", "]"] {
+        interner.intern(sym).unwrap();
+    }
+    interpreter.populate_standard_symbols(&interner);
+
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let (sym, params) = match token.find('(') {
+            Some(start) => (
+                &token[..start],
+                token[start + 1..token.len() - 1]
+                    .split(',')
+                    .map(|v| v.parse().unwrap())
+                    .collect(),
+            ),
+            None => (token, Vec::new()),
+        };
+        let id = interner.resolve_id(sym).unwrap();
+        state.push(id, 0.0, &params).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+fn config(kind: SplineKind, points_per_segment: u32) -> SmoothingConfig {
+    SmoothingConfig {
+        kind,
+        points_per_segment,
+    }
+}
+
+#[test]
+fn test_catmull_rom_passes_through_original_points() {
+    let skeleton = build("F +(30) F -(60) F +(45) F");
+    let smooth = skeleton.smoothed(&config(SplineKind::CatmullRom, 3));
+
+    let original = &skeleton.strands[0];
+    let resampled = &smooth.strands[0];
+    assert_eq!(resampled.len(), (original.len() - 1) * 4 + 1);
+
+    for (i, point) in original.iter().enumerate() {
+        assert!(resampled[i * 4].position.abs_diff_eq(point.position, 1e-5));
+        assert_relative_eq!(resampled[i * 4].radius, point.radius, epsilon = 1e-6);
+    }
+}
+
+#[test]
+fn test_straight_strand_stays_straight() {
+    let skeleton = build("F F F");
+    for kind in [SplineKind::CatmullRom, SplineKind::BSpline] {
+        let smooth = skeleton.smoothed(&config(kind, 2));
+        for point in &smooth.strands[0] {
+            assert_relative_eq!(point.position.x, 0.0, epsilon = 1e-5);
+            assert_relative_eq!(point.position.z, 0.0, epsilon = 1e-5);
+        }
+        // Evenly spaced input stays evenly spaced
+        let ys: Vec<f32> = smooth.strands[0].iter().map(|p| p.position.y).collect();
+        for (k, y) in ys.iter().enumerate() {
+            assert_relative_eq!(*y, k as f32 / 3.0, epsilon = 1e-5);
+        }
+    }
+}
+
+#[test]
+fn test_b_spline_keeps_endpoints_and_smooths_corners() {
+    let skeleton = build("F -(90) F");
+    let smooth = skeleton.smoothed(&config(SplineKind::BSpline, 3));
+
+    let original = &skeleton.strands[0];
+    let resampled = &smooth.strands[0];
+    assert!(
+        resampled[0]
+            .position
+            .abs_diff_eq(original[0].position, 1e-5)
+    );
+    assert!(
+        resampled
+            .last()
+            .unwrap()
+            .position
+            .abs_diff_eq(original[2].position, 1e-5)
+    );
+
+    // The corner at (0, 1, 0) is cut rather than interpolated
+    let corner = resampled[4].position;
+    assert!(corner.distance(original[1].position) > 0.05);
+}
+
+#[test]
+fn test_color_and_orientation_are_interpolated() {
+    let skeleton = build("'(0) F '(1) +(90) F");
+    let smooth = skeleton.smoothed(&config(SplineKind::CatmullRom, 1));
+
+    let strand = &smooth.strands[0];
+    // Midpoint of the second segment blends black and white
+    assert_relative_eq!(strand[3].color.x, 0.5, epsilon = 1e-5);
+
+    let a = skeleton.strands[0][1].rotation;
+    let b = skeleton.strands[0][2].rotation;
+    assert!(strand[3].rotation.abs_diff_eq(a.slerp(b, 0.5), 1e-5));
+}
+
+#[test]
+fn test_strand_breaks_and_topology_are_preserved() {
+    let skeleton = build("F +(20) F [ +(40) F -(30) F ] -(25) F F");
+    for kind in [SplineKind::CatmullRom, SplineKind::BSpline] {
+        let smooth = skeleton.smoothed(&config(kind, 4));
+        assert_eq!(smooth.strands.len(), skeleton.strands.len());
+
+        for (i, info) in smooth.strand_info.iter().enumerate() {
+            if let Some(parent) = info.parent {
+                let anchor = smooth.strands[parent][info.parent_point].position;
+                assert!(
+                    anchor.abs_diff_eq(smooth.strands[i][0].position, 1e-5),
+                    "{kind:?}: strand {i} detached from parent"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use approx::assert_relative_eq;
+use glam::Vec3;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{Skeleton, SkeletonBvh, SpatialElement, TurtleConfig, TurtleInterpreter};
+
+fn build(sequence: &str) -> Skeleton {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+    for sym in ["F", "+", "-", "&", "[", "]", "~"] {
+        interner.intern(sym).unwrap();
+    }
+    interpreter.populate_standard_symbols(&interner);
+
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let (sym, params) = match token.find('(') {
+            Some(start) => (
+                &token[..start],
+                token[start + 1..token.len() - 1]
+                    .split(',')
+                    .map(|v| v.parse().unwrap())
+                    .collect(),
+            ),
+            None => (token, Vec::new()),
+        };
+        let id = interner.resolve_id(sym).unwrap();
+        state.push(id, 0.0, &params).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+/// Brute-force reference: surface distance from `p` to every segment.
+fn brute_force_nearest(skeleton: &Skeleton, p: Vec3) -> f32 {
+    let mut best = f32::INFINITY;
+    for strand in &skeleton.strands {
+        for pair in strand.windows(2) {
+            let (a, b) = (pair[0].position, pair[1].position);
+            let ab = b - a;
+            let t = ((p - a).dot(ab) / ab.length_squared()).clamp(0.0, 1.0);
+            let radius = pair[0].radius.max(pair[1].radius);
+            best = best.min((a + ab * t).distance(p) - radius);
+        }
+    }
+    best.max(0.0)
+}
+
+const TREE: &str = "F(2) [ +(40) F F [ -(30) F ] ] [ -(50) F F &(30) F ] F F [ +(70) F ] F";
+
+#[test]
+fn test_bounds_and_sphere_enclose_everything() {
+    let skeleton = build(TREE);
+    let bvh = SkeletonBvh::build(&skeleton);
+
+    let bounds = bvh.bounds().unwrap();
+    let sphere = bvh.bounding_sphere().unwrap();
+    for strand in &skeleton.strands {
+        for point in strand {
+            let p = point.position;
+            assert!(p.cmpge(bounds.min).all() && p.cmple(bounds.max).all());
+            assert!(sphere.center.distance(p) + point.radius <= sphere.radius + 1e-5);
+        }
+    }
+}
+
+#[test]
+fn test_raycast_picks_trunk_segment() {
+    let skeleton = build("F(1) F(1) F(1)");
+    let bvh = SkeletonBvh::build(&skeleton);
+
+    // Shoot along -X through y = 1.5: hits the second segment halfway up
+    let hit = bvh
+        .raycast(Vec3::new(10.0, 1.5, 0.0), -Vec3::X, f32::INFINITY)
+        .unwrap();
+    assert_eq!(
+        hit.element,
+        SpatialElement::Segment {
+            strand: 0,
+            point: 1
+        }
+    );
+    let radius = skeleton.strands[0][1].radius;
+    assert_relative_eq!(hit.distance, 10.0 - radius, epsilon = 1e-4);
+    assert_relative_eq!(hit.t, 0.5, epsilon = 1e-4);
+
+    // Missing ray and a ray that stops short
+    assert!(
+        bvh.raycast(Vec3::new(10.0, 1.5, 5.0), -Vec3::X, f32::INFINITY)
+            .is_none()
+    );
+    assert!(
+        bvh.raycast(Vec3::new(10.0, 1.5, 0.0), -Vec3::X, 5.0)
+            .is_none()
+    );
+}
+
+#[test]
+fn test_raycast_hits_props() {
+    let skeleton = build("F(1) ~(3,0.5)");
+    let bvh = SkeletonBvh::build(&skeleton);
+
+    let hit = bvh
+        .raycast(Vec3::new(0.0, 5.0, 0.0), -Vec3::Y, f32::INFINITY)
+        .unwrap();
+    assert_eq!(hit.element, SpatialElement::Prop { index: 0 });
+    assert_relative_eq!(hit.distance, 3.5, epsilon = 1e-4);
+}
+
+#[test]
+fn test_nearest_matches_brute_force() {
+    let skeleton = build(TREE);
+    let bvh = SkeletonBvh::build(&skeleton);
+
+    for i in 0..50 {
+        let f = i as f32;
+        let p = Vec3::new((f * 0.37).sin() * 4.0, f * 0.15, (f * 0.71).cos() * 3.0);
+        let hit = bvh.nearest(p).unwrap();
+        assert_relative_eq!(
+            hit.distance,
+            brute_force_nearest(&skeleton, p),
+            epsilon = 1e-4
+        );
+    }
+}
+
+#[test]
+fn test_within_radius() {
+    let skeleton = build("F(1) F(1) F(1) F(1) F(1) F(1)");
+    let bvh = SkeletonBvh::build(&skeleton);
+    assert_eq!(bvh.len(), 6);
+
+    // Sphere around y = 2.5 touching segments 1..=3 only
+    let mut found: Vec<usize> = bvh
+        .within_radius(Vec3::new(0.0, 2.5, 0.0), 1.0)
+        .into_iter()
+        .map(|e| match e {
+            SpatialElement::Segment { point, .. } => point,
+            SpatialElement::Prop { .. } => unreachable!(),
+        })
+        .collect();
+    found.sort();
+    assert_eq!(found, vec![1, 2, 3]);
+
+    assert!(bvh.within_radius(Vec3::new(5.0, 0.0, 0.0), 1.0).is_empty());
+}
+
+#[test]
+fn test_empty_skeleton() {
+    let bvh = SkeletonBvh::build(&Skeleton::new());
+    assert!(bvh.is_empty());
+    assert!(bvh.bounds().is_none());
+    assert!(bvh.bounding_sphere().is_none());
+    assert!(bvh.nearest(Vec3::ZERO).is_none());
+    assert!(bvh.raycast(Vec3::ZERO, Vec3::X, 1.0).is_none());
+}
@@ -0,0 +1,239 @@
+use glam::{Mat4, Vec3};
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{
+    Axis, Projection, Skeleton, SvgConfig, TurtleConfig, TurtleInterpreter, write_svg,
+};
+
+fn build(sequence: &[(&str, &[f64])]) -> Skeleton {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+
+    for sym in ["F", "+", "&", "[", "]", "!", "'", "~", "{", ".", "}"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    let mut state = SymbiosState::new();
+    for (token, params) in sequence {
+        let id = interner.resolve_id(token).unwrap();
+        state.push(id, 0.0, params).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+fn export(skeleton: &Skeleton, config: &SvgConfig) -> String {
+    let mut out = Vec::new();
+    write_svg(skeleton, config, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn elements<'a>(svg: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{tag} ");
+    svg.lines().filter(|l| l.starts_with(&open)).collect()
+}
+
+fn attribute<'a>(element: &'a str, name: &str) -> &'a str {
+    let key = format!(" {name}=\"");
+    let start = element.find(&key).unwrap() + key.len();
+    let len = element[start..].find('"').unwrap();
+    &element[start..start + len]
+}
+
+fn points(element: &str) -> Vec<(f32, f32)> {
+    attribute(element, "points")
+        .split(' ')
+        .map(|pair| {
+            let (x, y) = pair.split_once(',').unwrap();
+            (x.parse().unwrap(), y.parse().unwrap())
+        })
+        .collect()
+}
+
+#[test]
+fn test_uniform_strand_is_one_polyline() {
+    let skeleton = build(&[("F", &[]), ("+", &[90.0]), ("F", &[]), ("F", &[])]);
+    let svg = export(&skeleton, &SvgConfig::default());
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    let polylines = elements(&svg, "polyline");
+    assert_eq!(polylines.len(), 1);
+
+    let points = points(polylines[0]);
+    assert_eq!(points.len(), 4);
+    // Y is flipped so the strand grows up the image
+    assert!(points[1].1 < points[0].1);
+    // The turn stays in the XY plane, so it shows up horizontally
+    assert!((points[2].0 - points[1].0).abs() > 0.5);
+}
+
+#[test]
+fn test_stroke_from_radius_and_color() {
+    let skeleton = build(&[
+        ("!", &[0.2]),
+        ("F", &[]),
+        ("'", &[1.0, 0.0, 0.0, 0.5]),
+        ("F", &[]),
+        ("!", &[0.05]),
+        ("F", &[]),
+    ]);
+    let svg = export(&skeleton, &SvgConfig::default());
+    let polylines = elements(&svg, "polyline");
+    assert_eq!(polylines.len(), 3);
+
+    let width = |i: usize| {
+        attribute(polylines[i], "stroke-width")
+            .parse::<f32>()
+            .unwrap()
+    };
+    // Stroke width is the diameter, i.e. the `!` width
+    approx::assert_relative_eq!(width(0), 2.0 * skeleton.strands[0][1].radius);
+    approx::assert_relative_eq!(width(0), 0.2);
+    approx::assert_relative_eq!(width(2), 0.05);
+    assert_eq!(attribute(polylines[1], "stroke"), "#ff0000");
+    assert_eq!(attribute(polylines[1], "stroke-opacity"), "0.5");
+    assert!(!polylines[0].contains("stroke-opacity"));
+
+    // Runs share their boundary point so the strand stays connected
+    assert_eq!(points(polylines[0]).last(), points(polylines[1]).first());
+}
+
+#[test]
+fn test_min_stroke_width_and_scale() {
+    let skeleton = build(&[("!", &[0.01]), ("F", &[])]);
+    let config = SvgConfig {
+        stroke_scale: 2.0,
+        min_stroke_width: 0.5,
+        ..Default::default()
+    };
+    let svg = export(&skeleton, &config);
+    let polyline = elements(&svg, "polyline")[0];
+    assert_eq!(attribute(polyline, "stroke-width"), "0.5");
+}
+
+#[test]
+fn test_props_as_markers() {
+    let skeleton = build(&[("F", &[]), ("~", &[3.0, 2.0]), ("F", &[]), ("~", &[])]);
+    let svg = export(&skeleton, &SvgConfig::default());
+    let circles = elements(&svg, "circle");
+    assert_eq!(circles.len(), skeleton.props.len());
+    assert_eq!(
+        attribute(circles[0], "class"),
+        format!("prop prop-{}", skeleton.props[0].prop_id)
+    );
+
+    let config = SvgConfig {
+        prop_marker_radius: 0.0,
+        ..Default::default()
+    };
+    assert!(elements(&export(&skeleton, &config), "circle").is_empty());
+}
+
+#[test]
+fn test_polygons_filled() {
+    let skeleton = build(&[
+        ("{", &[]),
+        (".", &[]),
+        ("F", &[]),
+        (".", &[]),
+        ("+", &[90.0]),
+        ("F", &[]),
+        (".", &[]),
+        ("}", &[]),
+    ]);
+    let svg = export(&skeleton, &SvgConfig::default());
+    let polygons = elements(&svg, "polygon");
+    assert_eq!(polygons.len(), skeleton.polygons.len());
+    assert_eq!(
+        points(polygons[0]).len(),
+        skeleton.polygons[0].vertices.len()
+    );
+
+    let config = SvgConfig {
+        polygons: false,
+        ..Default::default()
+    };
+    assert!(elements(&export(&skeleton, &config), "polygon").is_empty());
+}
+
+#[test]
+fn test_view_box_fits_drawing() {
+    let skeleton = build(&[("!", &[0.4]), ("F", &[]), ("F", &[])]);
+    let config = SvgConfig {
+        margin: 0.0,
+        ..Default::default()
+    };
+    let svg = export(&skeleton, &config);
+    let root = svg.lines().next().unwrap();
+    let view_box: Vec<f32> = attribute(root, "viewBox")
+        .split(' ')
+        .map(|v| v.parse().unwrap())
+        .collect();
+    let radius = skeleton.strands[0][1].radius;
+    let top = skeleton.strands[0].last().unwrap().position.y;
+
+    approx::assert_relative_eq!(view_box[0], -radius, epsilon = 1e-5);
+    approx::assert_relative_eq!(view_box[1], -top - radius, epsilon = 1e-5);
+    approx::assert_relative_eq!(view_box[2], 2.0 * radius, epsilon = 1e-5);
+    approx::assert_relative_eq!(view_box[3], top + 2.0 * radius, epsilon = 1e-5);
+    assert_eq!(attribute(root, "width"), "800");
+}
+
+#[test]
+fn test_plane_and_view_projections() {
+    // Pitching makes the strand lean out of the XY plane
+    let skeleton = build(&[("&", &[90.0]), ("F", &[])]);
+    let end = skeleton.strands[0][1].position;
+
+    let project = |projection: Projection| {
+        let config = SvgConfig {
+            projection,
+            ..Default::default()
+        };
+        let svg = export(&skeleton, &config);
+        points(elements(&svg, "polyline")[0])[1]
+    };
+
+    let (x, y) = project(Projection::Plane {
+        right: Axis::X,
+        up: Axis::Z,
+    });
+    approx::assert_relative_eq!(x, end.x, epsilon = 1e-5);
+    approx::assert_relative_eq!(y, -end.z, epsilon = 1e-5);
+
+    // A camera on +X looking at the origin sees world -Z to its right
+    let view = Mat4::look_at_rh(Vec3::X * 5.0, Vec3::ZERO, Vec3::Y);
+    let (x, y) = project(Projection::View(view));
+    approx::assert_relative_eq!(x, -end.z, epsilon = 1e-5);
+    approx::assert_relative_eq!(y, -end.y, epsilon = 1e-5);
+}
+
+#[test]
+fn test_empty_skeleton() {
+    let svg = export(&Skeleton::default(), &SvgConfig::default());
+    assert!(svg.contains("viewBox=\"0 0 1 1\""));
+    assert!(elements(&svg, "polyline").is_empty());
+}
+
+#[test]
+fn test_zero_width_drawing_keeps_bounded_height() {
+    let skeleton = build(&[("!", &[0.0]), ("F", &[]), ("F", &[])]);
+    let config = SvgConfig {
+        margin: 0.0,
+        ..Default::default()
+    };
+    let svg = export(&skeleton, &config);
+    let root = svg.lines().next().unwrap();
+    let view_box: Vec<f32> = attribute(root, "viewBox")
+        .split(' ')
+        .map(|v| v.parse().unwrap())
+        .collect();
+    let height: f32 = attribute(root, "height").parse().unwrap();
+
+    // The line is centered in a box at least a twentieth as wide as it is tall
+    let top = skeleton.strands[0].last().unwrap().position.y;
+    approx::assert_relative_eq!(view_box[3], top, epsilon = 1e-5);
+    approx::assert_relative_eq!(view_box[2], top * 0.05, epsilon = 1e-5);
+    approx::assert_relative_eq!(view_box[0], -view_box[2] / 2.0, epsilon = 1e-5);
+    approx::assert_relative_eq!(height, 800.0 * 20.0, epsilon = 1e-2);
+}
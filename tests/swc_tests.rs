@@ -0,0 +1,227 @@
+use glam::Vec3;
+use std::io;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{
+    Skeleton, TurtleConfig, TurtleInterpreter, load_swc, read_swc, save_swc, write_swc,
+};
+
+fn build(sequence: &[(&str, &[f64])]) -> Skeleton {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+
+    for sym in ["F", "f", "+", "[", "]", "!", ","] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    let mut state = SymbiosState::new();
+    for (token, params) in sequence {
+        let id = interner.resolve_id(token).unwrap();
+        state.push(id, 0.0, params).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+fn export(skeleton: &Skeleton) -> String {
+    let mut out = Vec::new();
+    write_swc(skeleton, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+/// Parses the sample lines into `(id, type, position, radius, parent)`.
+fn samples(swc: &str) -> Vec<(usize, u8, Vec3, f32, i64)> {
+    swc.lines()
+        .filter(|l| !l.starts_with('#'))
+        .map(|l| {
+            let f: Vec<&str> = l.split_whitespace().collect();
+            (
+                f[0].parse().unwrap(),
+                f[1].parse().unwrap(),
+                Vec3::new(
+                    f[2].parse().unwrap(),
+                    f[3].parse().unwrap(),
+                    f[4].parse().unwrap(),
+                ),
+                f[5].parse().unwrap(),
+                f[6].parse().unwrap(),
+            )
+        })
+        .collect()
+}
+
+fn read(swc: &str) -> io::Result<Skeleton> {
+    read_swc(swc.as_bytes())
+}
+
+#[test]
+fn test_export_links_branches_to_parent_samples() {
+    let skeleton = build(&[
+        (",", &[1.0]),
+        ("F", &[]),
+        ("[", &[]),
+        ("+", &[45.0]),
+        (",", &[3.0]),
+        ("F", &[]),
+        ("]", &[]),
+        ("F", &[]),
+    ]);
+    assert_eq!(skeleton.strands.len(), 3);
+    let swc = export(&skeleton);
+    let samples = samples(&swc);
+
+    // The branch and the resumed trunk continue from the trunk tip instead of repeating it
+    assert_eq!(samples.len(), 4);
+    assert_eq!(
+        samples.iter().map(|s| s.4).collect::<Vec<_>>(),
+        vec![-1, 1, 2, 2]
+    );
+    assert_eq!(
+        samples.iter().map(|s| s.1).collect::<Vec<_>>(),
+        vec![1, 1, 3, 1]
+    );
+    assert_eq!(
+        samples.iter().map(|s| s.0).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4]
+    );
+
+    let branch_tip = skeleton.strands[1][1];
+    assert!(samples[2].2.abs_diff_eq(branch_tip.position, 1e-5));
+    approx::assert_relative_eq!(samples[2].3, branch_tip.radius);
+}
+
+#[test]
+fn test_disconnected_strand_becomes_root() {
+    let skeleton = build(&[("F", &[]), ("f", &[]), ("F", &[])]);
+    let samples = samples(&export(&skeleton));
+    assert_eq!(samples.len(), 4);
+    assert_eq!(
+        samples.iter().map(|s| s.4).collect::<Vec<_>>(),
+        vec![-1, 1, -1, 3]
+    );
+}
+
+#[test]
+fn test_import_builds_strands_and_topology() {
+    // A soma with two dendrites, listed out of order, one of which forks
+    let swc = "\
+# synthetic neuron
+1 1 0 0 0 2.0 -1
+3 3 0 -1 0 0.5 1
+2 3 0 1 0 0.5 1   # first dendrite
+4 3 0 2 0 0.4 2
+5 3 1 2 0 0.3 2
+
+6 4 0 -2 0 0.4 3
+";
+    let skeleton = read(swc).unwrap();
+
+    // Soma -> 3 -> 6 continues, 2 branches off the soma, and 5 forks off 2
+    assert_eq!(skeleton.strands.len(), 3);
+    let positions =
+        |s: usize| -> Vec<Vec3> { skeleton.strands[s].iter().map(|p| p.position).collect() };
+    assert_eq!(
+        positions(0),
+        vec![
+            Vec3::ZERO,
+            Vec3::new(0.0, -1.0, 0.0),
+            Vec3::new(0.0, -2.0, 0.0)
+        ]
+    );
+    assert_eq!(
+        positions(1),
+        vec![
+            Vec3::ZERO,
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0)
+        ]
+    );
+    assert_eq!(
+        positions(2),
+        vec![Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 2.0, 0.0)]
+    );
+
+    assert_eq!(skeleton.strand_info[0].parent, None);
+    assert_eq!(skeleton.strand_info[1].parent, Some(0));
+    assert_eq!(skeleton.strand_info[1].parent_point, 0);
+    assert_eq!(skeleton.strand_info[2].parent, Some(1));
+    assert_eq!(skeleton.strand_info[2].parent_point, 1);
+    assert_eq!(skeleton.strand_info[2].depth, 2);
+
+    let first = &skeleton.strands[0];
+    assert_eq!(first[0].material_id, 1);
+    assert_eq!(first[2].material_id, 4);
+    approx::assert_relative_eq!(first[0].radius, 2.0);
+    approx::assert_relative_eq!(skeleton.strands[2][1].radius, 0.3);
+
+    // Headings follow the strands
+    let heading = first[1].rotation * Vec3::Y;
+    assert!(heading.abs_diff_eq(Vec3::NEG_Y, 1e-5));
+}
+
+#[test]
+fn test_round_trip() {
+    let skeleton = build(&[
+        ("!", &[0.4]),
+        ("F", &[]),
+        ("[", &[]),
+        ("+", &[30.0]),
+        ("!", &[0.2]),
+        ("F", &[]),
+        ("F", &[]),
+        ("]", &[]),
+        ("F", &[]),
+        ("[", &[]),
+        ("+", &[-30.0]),
+        (",", &[2.0]),
+        ("F", &[]),
+        ("]", &[]),
+    ]);
+    let imported = read(&export(&skeleton)).unwrap();
+
+    let segments = |s: &Skeleton| -> usize {
+        s.strands
+            .iter()
+            .map(|strand| strand.len().saturating_sub(1))
+            .sum()
+    };
+    assert_eq!(segments(&imported), segments(&skeleton));
+    assert_eq!(export(&imported), export(&skeleton));
+
+    // Strands are regrouped at forks, but every point survives
+    for point in skeleton.strands.iter().flatten() {
+        assert!(
+            imported
+                .strands
+                .iter()
+                .flatten()
+                .any(|p| p.position.abs_diff_eq(point.position, 1e-5))
+        );
+    }
+}
+
+#[test]
+fn test_invalid_input() {
+    let cases = [
+        "1 1 0 0 0 1",
+        "1 1 0 0 0 1 -1\n1 3 0 1 0 1 1",
+        "1 1 0 0 0 1 -1\n2 3 0 1 0 1 7",
+        "1 3 0 0 0 1 2\n2 3 0 1 0 1 1",
+        "1 300 0 0 0 1 -1",
+        "1 1 0 zero 0 1 -1",
+    ];
+    for swc in cases {
+        let error = read(swc).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{swc}");
+        assert!(error.to_string().starts_with("SWC line"), "{error}");
+    }
+}
+
+#[test]
+fn test_save_and_load() {
+    let skeleton = build(&[("F", &[]), ("[", &[]), ("+", &[]), ("F", &[]), ("]", &[])]);
+    let path = std::env::temp_dir().join(format!("symbios_swc_{}.swc", std::process::id()));
+    save_swc(&skeleton, &path).unwrap();
+    let loaded = load_swc(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(export(&loaded), export(&skeleton));
+}
@@ -0,0 +1,128 @@
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{Skeleton, StrandInfo, TurtleConfig, TurtleInterpreter};
+
+fn build(sequence: &str) -> Skeleton {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(TurtleConfig::default());
+    for sym in ["F", "f", "+", "[", "]"] {
+        interner.intern(sym).unwrap();
+    }
+    interpreter.populate_standard_symbols(&interner);
+
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let id = interner.resolve_id(token).unwrap();
+        state.push(id, 0.0, &[]).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+#[test]
+fn test_nested_branch_topology() {
+    let skeleton = build("F [ + F [ + F ] ] F");
+
+    // s0 trunk, s1 branch, s2 sub-branch, s3 resumed branch, s4 resumed trunk
+    assert_eq!(skeleton.strands.len(), 5);
+    assert_eq!(skeleton.strand_info.len(), skeleton.strands.len());
+
+    let expected = [
+        StrandInfo {
+            parent: None,
+            parent_point: 0,
+            depth: 0,
+            detached: false,
+        },
+        StrandInfo {
+            parent: Some(0),
+            parent_point: 1,
+            depth: 1,
+            detached: false,
+        },
+        StrandInfo {
+            parent: Some(1),
+            parent_point: 1,
+            depth: 2,
+            detached: false,
+        },
+        StrandInfo {
+            parent: Some(1),
+            parent_point: 1,
+            depth: 1,
+            detached: false,
+        },
+        StrandInfo {
+            parent: Some(0),
+            parent_point: 1,
+            depth: 0,
+            detached: false,
+        },
+    ];
+    assert_eq!(skeleton.strand_info, expected);
+
+    // Attachment points coincide with the first point of each child strand
+    for (i, info) in skeleton.strand_info.iter().enumerate() {
+        if let Some(parent) = info.parent {
+            let attach = skeleton.strands[parent][info.parent_point].position;
+            assert!(attach.abs_diff_eq(skeleton.strands[i][0].position, 1e-5));
+        }
+    }
+}
+
+#[test]
+fn test_traversal_helpers() {
+    let skeleton = build("F [ + F [ + F ] ] F");
+
+    assert_eq!(skeleton.roots().collect::<Vec<_>>(), vec![0]);
+    assert_eq!(skeleton.children(0).collect::<Vec<_>>(), vec![1, 4]);
+    assert_eq!(skeleton.children(1).collect::<Vec<_>>(), vec![2, 3]);
+    assert_eq!(skeleton.children(2).count(), 0);
+
+    assert_eq!(skeleton.path_to_root(2), vec![2, 1, 0]);
+    assert_eq!(skeleton.path_to_root(0), vec![0]);
+
+    assert_eq!(skeleton.subtree(1), vec![1, 2, 3]);
+    assert_eq!(skeleton.subtree(0), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn test_move_records_continued_strand() {
+    let skeleton = build("F f F");
+
+    assert_eq!(skeleton.strands.len(), 2);
+    assert_eq!(
+        skeleton.strand_info[1],
+        StrandInfo {
+            parent: Some(0),
+            parent_point: 1,
+            depth: 0,
+            detached: true,
+        }
+    );
+
+    // The two strands are not connected, so both are roots
+    assert_eq!(skeleton.parent(1), None);
+    assert_eq!(skeleton.roots().collect::<Vec<_>>(), vec![0, 1]);
+    assert_eq!(skeleton.children(0).count(), 0);
+    assert_eq!(skeleton.subtree(0), vec![0]);
+}
+
+#[test]
+fn test_strand_info_without_detached_flag_deserializes() {
+    let info: StrandInfo =
+        serde_json::from_str(r#"{"parent":0,"parent_point":1,"depth":1}"#).unwrap();
+    assert!(!info.detached);
+    assert_eq!(info.parent, Some(0));
+}
+
+#[test]
+fn test_strands_without_topology_are_roots() {
+    let mut skeleton = build("F [ F ]");
+    // Strands pushed directly carry no topology and are treated as roots
+    skeleton.strands.push(skeleton.strands[0].clone());
+    let last = skeleton.strands.len() - 1;
+
+    assert_eq!(skeleton.strand_info(last), StrandInfo::default());
+    assert_eq!(skeleton.parent(last), None);
+    assert!(skeleton.roots().any(|r| r == last));
+    assert_eq!(skeleton.subtree(last), vec![last]);
+}
@@ -0,0 +1,168 @@
+use approx::assert_relative_eq;
+use glam::Vec3;
+use std::sync::Arc;
+use symbios::{SymbiosState, SymbolTable};
+use symbios_turtle_3d::{
+    Skeleton, Tropism, TropismField, TurtleConfig, TurtleInterpreter, TurtleOp, TurtleState,
+};
+
+fn setup(config: TurtleConfig) -> (TurtleInterpreter, SymbolTable) {
+    let mut interner = SymbolTable::new();
+    let mut interpreter = TurtleInterpreter::new(config);
+
+    for sym in ["F", "&", "[", "]", "T", "E"] {
+        interner.intern(sym).unwrap();
+    }
+
+    interpreter.populate_standard_symbols(&interner);
+    interpreter.set_op(interner.resolve_id("T").unwrap(), TurtleOp::SetTropism);
+    interpreter.set_op(interner.resolve_id("E").unwrap(), TurtleOp::SetElasticity);
+    (interpreter, interner)
+}
+
+fn run(sequence: &str, interpreter: &TurtleInterpreter, interner: &SymbolTable) -> Skeleton {
+    let mut state = SymbiosState::new();
+    for token in sequence.split_whitespace() {
+        let (sym, params) = match token.find('(') {
+            Some(start) => (
+                &token[..start],
+                token[start + 1..token.len() - 1]
+                    .split(',')
+                    .map(|v| v.parse().unwrap())
+                    .collect(),
+            ),
+            None => (token, Vec::new()),
+        };
+        let id = interner.resolve_id(sym).unwrap();
+        state.push(id, 0.0, &params).unwrap();
+    }
+    interpreter.build_skeleton(&state)
+}
+
+fn end_of(skeleton: &Skeleton, strand: usize) -> Vec3 {
+    skeleton.strands[strand].last().unwrap().position
+}
+
+#[test]
+fn test_branch_tropism_is_restored_on_pop() {
+    let (interpreter, interner) = setup(TurtleConfig::default());
+
+    // Branch bends toward -Y; the trunk continuation has no tropism
+    let skeleton = run(
+        "F(1) [ T(0,-1,0) E(0.3) &(90) F(1) F(1) F(1) ] F(1) F(1)",
+        &interpreter,
+        &interner,
+    );
+
+    let branch_end = end_of(&skeleton, 1);
+    assert!(branch_end.y < 1.0, "Branch should droop, got {branch_end}");
+
+    let trunk_end = end_of(&skeleton, 2);
+    assert_relative_eq!(trunk_end.x, 0.0, epsilon = 1e-5);
+    assert_relative_eq!(trunk_end.y, 3.0, epsilon = 1e-5);
+    assert_relative_eq!(trunk_end.z, 0.0, epsilon = 1e-5);
+}
+
+#[test]
+fn test_set_tropism_without_params_disables_bending() {
+    let (interpreter, interner) = setup(TurtleConfig {
+        tropism: Some(Vec3::X),
+        elasticity: 0.5,
+        ..Default::default()
+    });
+
+    let bent = run("F(1) F(1)", &interpreter, &interner);
+    assert!(end_of(&bent, 0).x > 0.1);
+
+    let straight = run("T F(1) F(1)", &interpreter, &interner);
+    assert_relative_eq!(end_of(&straight, 0).x, 0.0, epsilon = 1e-5);
+}
+
+#[test]
+fn test_point_attractor_and_repeller() {
+    let light = Vec3::new(10.0, 0.0, 0.0);
+    let attract = |elasticity| TurtleConfig {
+        tropisms: vec![Tropism::Point {
+            position: light,
+            elasticity,
+        }],
+        ..Default::default()
+    };
+
+    let (interpreter, interner) = setup(attract(0.2));
+    let toward = run("F(1) F(1) F(1)", &interpreter, &interner);
+    assert!(end_of(&toward, 0).x > 0.1);
+
+    let (interpreter, interner) = setup(attract(-0.2));
+    let away = run("F(1) F(1) F(1)", &interpreter, &interner);
+    assert!(end_of(&away, 0).x < -0.1);
+}
+
+struct Downdraft;
+
+impl TropismField for Downdraft {
+    fn sample(&self, _position: Vec3) -> Vec3 {
+        -Vec3::Z
+    }
+}
+
+#[test]
+fn test_vector_field_matches_equivalent_directional_term() {
+    let (field, interner) = setup(TurtleConfig {
+        tropisms: vec![Tropism::Field {
+            field: Arc::new(Downdraft),
+            elasticity: 0.25,
+        }],
+        ..Default::default()
+    });
+    let (directional, _) = setup(TurtleConfig {
+        tropisms: vec![Tropism::Directional {
+            direction: -Vec3::Z,
+            elasticity: 0.25,
+        }],
+        ..Default::default()
+    });
+
+    let a = end_of(&run("F(1) F(1) F(1)", &field, &interner), 0);
+    let b = end_of(&run("F(1) F(1) F(1)", &directional, &interner), 0);
+    assert!(a.z < -0.1);
+    assert!(a.abs_diff_eq(b, 1e-6));
+}
+
+#[test]
+fn test_roots_and_shoots_in_one_plant() {
+    // Shoots bend toward a light on the +X side; a root branch adds strong gravity
+    let (interpreter, interner) = setup(TurtleConfig {
+        tropisms: vec![Tropism::Point {
+            position: Vec3::new(20.0, 5.0, 0.0),
+            elasticity: 0.1,
+        }],
+        ..Default::default()
+    });
+
+    let skeleton = run(
+        "[ T(0,-1,0) E(0.6) &(120) F(1) F(1) F(1) F(1) ] F(1) F(1) F(1)",
+        &interpreter,
+        &interner,
+    );
+
+    let root_end = end_of(&skeleton, 0);
+    let shoot_end = end_of(&skeleton, 1);
+    assert!(root_end.y < -1.0, "Root should grow down, got {root_end}");
+    assert!(
+        shoot_end.y > 1.0 && shoot_end.x > 0.0,
+        "Shoot should lean to light, got {shoot_end}"
+    );
+}
+
+#[test]
+fn test_turtle_state_without_tropism_fields_deserializes() {
+    let mut json = serde_json::to_value(TurtleState::default()).unwrap();
+    let fields = json.as_object_mut().unwrap();
+    fields.remove("tropism");
+    fields.remove("elasticity");
+
+    let state: TurtleState = serde_json::from_value(json).unwrap();
+    assert_eq!(state.tropism, None);
+    assert_eq!(state.elasticity, 0.0);
+}